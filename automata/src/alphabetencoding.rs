@@ -0,0 +1,64 @@
+use crate::error::{Error, Result};
+
+// bytes that can appear in an encoded token as themselves. everything else becomes xHH
+pub(crate) fn is_literal(b: u8) -> bool {
+    // ascii graphics only (so no whitespace or unprintables), and never the unsafe : \ or the escape char x
+    b.is_ascii_graphic() && b != b':' && b != b'\\' && b != b'x'
+}
+
+// works on raw bytes so every value 0x00-0xff survives a round trip through decode
+pub fn encode(decoded_token: &[u8]) -> String {
+    let mut encoded_token = String::with_capacity(decoded_token.len());
+
+    for &b in decoded_token {
+        if is_literal(b) {
+            // keep literal
+            encoded_token.push(b as char);
+        } else {
+            // encode in hex
+            encoded_token.push_str(&format!("x{:02x}", b));
+        }
+    }
+    encoded_token
+}
+
+// hands back the exact bytes. accepts either hex case and a literal space, since hand written scan.u files use both
+pub fn decode(encoded_token: &str) -> Result<Vec<u8>> {
+    let bytes = encoded_token.as_bytes();
+    let mut index = 0;
+
+    let mut decoded_token = Vec::with_capacity(bytes.len());
+    while index < bytes.len() {
+        let bad_escape = || Error::BadEscape { token: encoded_token.to_string(), offset: index };
+
+        if bytes[index] == b'x' {
+            // need exactly two hex digits after the x. from_str_radix alone would let a sign like x+f through
+            let (hi, lo) = match (bytes.get(index + 1), bytes.get(index + 2)) {
+                (Some(&hi), Some(&lo)) if hi.is_ascii_hexdigit() && lo.is_ascii_hexdigit() => (hi, lo),
+                _ => return Err(bad_escape()), // malformed token
+            };
+            decoded_token.push(hex_value(hi) << 4 | hex_value(lo));
+
+            index += 3;
+        } else {
+            let c = bytes[index];
+            if is_literal(c) || c == b' ' {
+                decoded_token.push(c);
+            } else {
+                // invalid literal
+                return Err(bad_escape());
+            }
+            index += 1;
+        }
+    }
+    Ok(decoded_token)
+}
+
+// caller has already checked that digit is a hex digit
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{ErrorKind, Read, Write};
+
+use crate::dfa::{StateRow, DFA};
+use crate::error::{Error, Result};
+use crate::nfa::{NfaStateRow, NFA};
+
+// precompiled automata, so a scanner doesn't have to reparse its .tt files every run
+//
+// every file starts with the 4 byte magic, a u16 version and a u8 kind. all integers are little endian.
+// an automaton body is
+//     columns u32, alphabet count u16 then (byte u8, column u16) pairs,
+//     states u32, start u32, accepting flags (one bit per state, lowest bit first),
+//     layout u8 then the transitions
+// dense layout (DFA only) is states * columns u32s with u32::MAX for E. sparse layout is, for each state,
+// an edge count u32 then (column u32, destination u32) pairs. an NFA cell with several destinations is
+// several edges on the same column
+// a scanner is the count of recognizers u32, then for each one its token id, an optional token value and a DFA body
+const MAGIC: &[u8; 4] = b"ATMA";
+pub const VERSION: u16 = 1;
+
+const KIND_DFA: u8 = 0;
+const KIND_NFA: u8 = 1;
+const KIND_SCANNER: u8 = 2;
+
+const LAYOUT_DENSE: u8 = 0;
+const LAYOUT_SPARSE: u8 = 1;
+
+const NO_TRANSITION: u32 = u32::MAX;
+
+// one line of a scan.u: the DFA and what to call the tokens it finds
+#[derive(Debug, Clone)]
+pub struct CompiledRecognizer {
+    pub dfa: DFA,
+    pub token_id: String,
+    pub token_value: Option<String>,
+}
+
+fn malformed<T>(message: impl Into<String>) -> Result<T> {
+    Err(Error::MalformedBinary { message: message.into() })
+}
+
+// true if the bytes start like a file written by this module. LUTHOR uses it to tell a compiled scanner from a scan.u
+pub fn is_compiled(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+struct Writer<'w, W: Write> {
+    inner: &'w mut W,
+}
+
+impl<W: Write> Writer<'_, W> {
+    fn bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        Ok(self.inner.write_all(bytes)?)
+    }
+
+    fn u8(&mut self, value: u8) -> Result<()> {
+        self.bytes(&[value])
+    }
+
+    fn u16(&mut self, value: u16) -> Result<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn u32(&mut self, value: u32) -> Result<()> {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn string(&mut self, value: &str) -> Result<()> {
+        self.u32(value.len() as u32)?;
+        self.bytes(value.as_bytes())
+    }
+
+    fn header(&mut self, kind: u8) -> Result<()> {
+        self.bytes(MAGIC)?;
+        self.u16(VERSION)?;
+        self.u8(kind)
+    }
+
+    fn alphabet(&mut self, len_alphabet: usize, alphabet: &HashMap<u8, usize>) -> Result<()> {
+        self.u32(len_alphabet as u32)?;
+        let mut pairs: Vec<(u8, usize)> = alphabet.iter().map(|(&b, &c)| (b, c)).collect();
+        pairs.sort_unstable();
+        self.u16(pairs.len() as u16)?;
+        for (byte, column) in pairs {
+            self.u8(byte)?;
+            self.u16(column as u16)?;
+        }
+        Ok(())
+    }
+
+    fn accepting(&mut self, accepting: impl Iterator<Item = bool>) -> Result<()> {
+        let mut flags: Vec<u8> = Vec::new();
+        for (i, accepting) in accepting.enumerate() {
+            if i % 8 == 0 {
+                flags.push(0);
+            }
+            if accepting {
+                *flags.last_mut().unwrap() |= 1 << (i % 8);
+            }
+        }
+        self.bytes(&flags)
+    }
+
+    fn dfa_body(&mut self, dfa: &DFA) -> Result<()> {
+        self.alphabet(dfa.len_alphabet, &dfa.alphabet)?;
+        self.u32(dfa.states.len() as u32)?;
+        self.u32(dfa.start_state as u32)?;
+        self.accepting(dfa.states.iter().map(|s| s.accepting))?;
+
+        // dense costs 4 bytes a cell, sparse 8 bytes an edge plus 4 a state. take whichever is smaller
+        let cells = dfa.states.len() * dfa.len_alphabet;
+        let edges = dfa.states.iter().map(|s| s.transitions.iter().flatten().count()).sum::<usize>();
+        if 4 * cells <= 8 * edges + 4 * dfa.states.len() {
+            self.u8(LAYOUT_DENSE)?;
+            for state in &dfa.states {
+                for transition in &state.transitions {
+                    self.u32(transition.unwrap_or(NO_TRANSITION))?;
+                }
+            }
+        } else {
+            self.u8(LAYOUT_SPARSE)?;
+            for state in &dfa.states {
+                self.u32(state.transitions.iter().flatten().count() as u32)?;
+                for (column, transition) in state.transitions.iter().enumerate() {
+                    if let Some(to) = transition {
+                        self.u32(column as u32)?;
+                        self.u32(*to)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'r, R: Read> {
+    inner: &'r mut R,
+}
+
+impl<R: Read> Reader<'_, R> {
+    fn bytes(&mut self, buf: &mut [u8], what: &str) -> Result<()> {
+        match self.inner.read_exact(buf) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => malformed(format!("truncated while reading {}", what)),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    fn u8(&mut self, what: &str) -> Result<u8> {
+        let mut buf = [0; 1];
+        self.bytes(&mut buf, what)?;
+        Ok(buf[0])
+    }
+
+    fn u16(&mut self, what: &str) -> Result<u16> {
+        let mut buf = [0; 2];
+        self.bytes(&mut buf, what)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32> {
+        let mut buf = [0; 4];
+        self.bytes(&mut buf, what)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn string(&mut self, what: &str) -> Result<String> {
+        let len = self.u32(what)? as usize;
+        // read through take so a corrupt length can't make us allocate gigabytes up front
+        let mut buf = Vec::new();
+        self.inner.by_ref().take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() != len {
+            return malformed(format!("truncated while reading {}", what));
+        }
+        String::from_utf8(buf).or_else(|_| malformed(format!("{} is not valid utf-8", what)))
+    }
+
+    fn header(&mut self, kind: u8) -> Result<()> {
+        let mut magic = [0; 4];
+        self.bytes(&mut magic, "the header")?;
+        if &magic != MAGIC {
+            return malformed("not a compiled automaton file");
+        }
+        let version = self.u16("the header")?;
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion { found: version, supported: VERSION });
+        }
+        let found = self.u8("the header")?;
+        if found != kind {
+            let name = |kind: u8| match kind {
+                KIND_DFA => "a DFA",
+                KIND_NFA => "an NFA",
+                KIND_SCANNER => "a scanner",
+                _ => "an unknown kind of file",
+            };
+            return malformed(format!("expected {}, found {}", name(kind), name(found)));
+        }
+        Ok(())
+    }
+
+    // the column count and the byte -> column map
+    fn alphabet(&mut self) -> Result<(usize, HashMap<u8, usize>)> {
+        let len_alphabet = self.u32("the alphabet")? as usize;
+        if len_alphabet > u16::MAX as usize {
+            return malformed(format!("{} columns is more than the format allows", len_alphabet));
+        }
+        let count = self.u16("the alphabet")?;
+        let mut alphabet = HashMap::new();
+        for _ in 0..count {
+            let byte = self.u8("the alphabet")?;
+            let column = self.u16("the alphabet")? as usize;
+            if column >= len_alphabet {
+                return malformed(format!("symbol 0x{:02x} is in column {} of {}", byte, column, len_alphabet));
+            }
+            alphabet.insert(byte, column);
+        }
+        Ok((len_alphabet, alphabet))
+    }
+
+    // state count, start state and accepting flags
+    fn states(&mut self) -> Result<(usize, u32, Vec<bool>)> {
+        let num_states = self.u32("the state count")? as usize;
+        if num_states == 0 {
+            return Err(Error::EmptyAutomaton);
+        }
+        let start = self.u32("the start state")?;
+        if start as usize >= num_states {
+            return malformed(format!("start state {} is out of range for {} states", start, num_states));
+        }
+
+        let mut flags = Vec::new();
+        self.inner.by_ref().take(num_states.div_ceil(8) as u64).read_to_end(&mut flags)?;
+        if flags.len() != num_states.div_ceil(8) {
+            return malformed("truncated while reading the accepting flags");
+        }
+        let accepting = (0..num_states).map(|i| flags[i / 8] & (1 << (i % 8)) != 0).collect();
+        Ok((num_states, start, accepting))
+    }
+
+    // the sparse edge list of one state, checked against the table size
+    fn edges(&mut self, num_states: usize, len_alphabet: usize) -> Result<Vec<(usize, u32)>> {
+        let count = self.u32("the transitions")?;
+        let mut edges = Vec::new();
+        for _ in 0..count {
+            let column = self.u32("the transitions")? as usize;
+            let to = self.u32("the transitions")?;
+            if column >= len_alphabet || to as usize >= num_states {
+                return malformed(format!("transition on column {} to state {} is out of range", column, to));
+            }
+            edges.push((column, to));
+        }
+        Ok(edges)
+    }
+
+    fn dfa_body(&mut self) -> Result<DFA> {
+        let (len_alphabet, alphabet) = self.alphabet()?;
+        let (num_states, start, accepting) = self.states()?;
+
+        let mut states: Vec<StateRow> = Vec::new();
+        match self.u8("the layout")? {
+            LAYOUT_DENSE => {
+                for (id, accepting) in accepting.into_iter().enumerate() {
+                    let mut transitions = Vec::with_capacity(len_alphabet.min(256));
+                    for _ in 0..len_alphabet {
+                        let to = self.u32("the transitions")?;
+                        if to == NO_TRANSITION {
+                            transitions.push(None);
+                        } else if (to as usize) < num_states {
+                            transitions.push(Some(to));
+                        } else {
+                            return malformed(format!("transition to state {} is out of range", to));
+                        }
+                    }
+                    states.push(StateRow { accepting, state_id: id as u32, transitions });
+                }
+            }
+            LAYOUT_SPARSE => {
+                for (id, accepting) in accepting.into_iter().enumerate() {
+                    let mut transitions = vec![None; len_alphabet];
+                    for (column, to) in self.edges(num_states, len_alphabet)? {
+                        if transitions[column].replace(to).is_some() {
+                            return malformed(format!("state {} has two transitions on column {}", id, column));
+                        }
+                    }
+                    states.push(StateRow { accepting, state_id: id as u32, transitions });
+                }
+            }
+            layout => return malformed(format!("unknown transition layout {}", layout)),
+        }
+        DFA::with_start_state(states, alphabet, start)
+    }
+}
+
+impl DFA {
+    // write the DFA in the binary format, dense or sparse depending on which is smaller
+    pub fn save<W: Write>(&self, output: &mut W) -> Result<()> {
+        let mut writer = Writer { inner: output };
+        writer.header(KIND_DFA)?;
+        writer.dfa_body(self)
+    }
+
+    // read a DFA written by save
+    pub fn load<R: Read>(input: &mut R) -> Result<DFA> {
+        let mut reader = Reader { inner: input };
+        reader.header(KIND_DFA)?;
+        reader.dfa_body()
+    }
+}
+
+impl NFA {
+    // write the NFA in the binary format. NFAs are always written sparse
+    pub fn save<W: Write>(&self, output: &mut W) -> Result<()> {
+        let mut writer = Writer { inner: output };
+        writer.header(KIND_NFA)?;
+        writer.alphabet(self.len_alphabet, &self.alphabet)?;
+        writer.u32(self.states.len() as u32)?;
+        writer.u32(self.start_state_id)?;
+        writer.accepting(self.states.iter().map(|s| s.accepting))?;
+
+        writer.u8(LAYOUT_SPARSE)?;
+        for state in &self.states {
+            writer.u32(state.transitions.iter().map(|cell| cell.len()).sum::<usize>() as u32)?;
+            for (column, cell) in state.transitions.iter().enumerate() {
+                // sorted so the same NFA always gives the same bytes
+                let mut destinations: Vec<u32> = cell.iter().copied().collect();
+                destinations.sort_unstable();
+                for to in destinations {
+                    writer.u32(column as u32)?;
+                    writer.u32(to)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // read an NFA written by save
+    pub fn load<R: Read>(input: &mut R) -> Result<NFA> {
+        let mut reader = Reader { inner: input };
+        reader.header(KIND_NFA)?;
+        let (len_alphabet, alphabet) = reader.alphabet()?;
+        let (num_states, start, accepting) = reader.states()?;
+
+        let layout = reader.u8("the layout")?;
+        if layout != LAYOUT_SPARSE {
+            return malformed(format!("NFAs are stored sparse, found layout {}", layout));
+        }
+        let mut states: Vec<NfaStateRow> = Vec::new();
+        for (id, accepting) in accepting.into_iter().enumerate() {
+            let mut transitions = vec![HashSet::new(); len_alphabet];
+            for (column, to) in reader.edges(num_states, len_alphabet)? {
+                transitions[column].insert(to);
+            }
+            states.push(NfaStateRow { accepting, state_id: id as u32, transitions });
+        }
+        NFA::new(states, alphabet, start)
+    }
+}
+
+// write every recognizer of a scanner into one file, in scanner order
+pub fn save_scanner<W: Write>(recognizers: &[CompiledRecognizer], output: &mut W) -> Result<()> {
+    let mut writer = Writer { inner: output };
+    writer.header(KIND_SCANNER)?;
+    writer.u32(recognizers.len() as u32)?;
+    for recognizer in recognizers {
+        writer.string(&recognizer.token_id)?;
+        match &recognizer.token_value {
+            Some(value) => {
+                writer.u8(1)?;
+                writer.string(value)?;
+            }
+            None => writer.u8(0)?,
+        }
+        writer.dfa_body(&recognizer.dfa)?;
+    }
+    Ok(())
+}
+
+// read a scanner written by save_scanner
+pub fn load_scanner<R: Read>(input: &mut R) -> Result<Vec<CompiledRecognizer>> {
+    let mut reader = Reader { inner: input };
+    reader.header(KIND_SCANNER)?;
+    let count = reader.u32("the recognizer count")?;
+    let mut recognizers = Vec::new();
+    for _ in 0..count {
+        let token_id = reader.string("a token id")?;
+        let token_value = match reader.u8("a token value")? {
+            0 => None,
+            1 => Some(reader.string("a token value")?),
+            flag => return malformed(format!("bad token value flag {}", flag)),
+        };
+        let dfa = reader.dfa_body()?;
+        recognizers.push(CompiledRecognizer { dfa, token_id, token_value });
+    }
+    Ok(recognizers)
+}
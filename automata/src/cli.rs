@@ -0,0 +1,19 @@
+// the command line shape every tool shares: flags are anything starting with --, in any position, and
+// everything else is a positional arg in the order given
+
+// apply gets each flag whole and says whether it knew it. a flag nobody knows is an error, so is any error
+// apply gives back. messages are for the tool to print after ERROR:
+pub fn split_args<F>(args: &[String], mut apply: F) -> Result<Vec<String>, String>
+where
+    F: FnMut(&str) -> Result<bool, String>,
+{
+    let mut positional = Vec::new();
+    for arg in args {
+        if !arg.starts_with("--") {
+            positional.push(arg.clone());
+        } else if !apply(arg)? {
+            return Err(format!("unknown flag {}", arg));
+        }
+    }
+    Ok(positional)
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use crate::dfa::DFA;
+
+// which kind of rust DFA::to_rust writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustStyle {
+    // one match arm per (state, bytes) pair. no tables, the compiler sees the whole machine
+    Match,
+    // static arrays for the byte -> column map, the accepting flags and the transitions, walked by a small loop
+    Table,
+}
+
+// sorted, deduplicated symbols as runs of consecutive bytes (first, last)
+pub(crate) fn byte_runs(symbols: &[u8]) -> Vec<(u8, u8)> {
+    let mut sorted = symbols.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut runs: Vec<(u8, u8)> = Vec::new();
+    for symbol in sorted {
+        match runs.last_mut() {
+            Some(run) if run.1.checked_add(1) == Some(symbol) => run.1 = symbol,
+            _ => runs.push((symbol, symbol)),
+        }
+    }
+    runs
+}
+
+fn byte_literal(byte: u8) -> String {
+    if byte.is_ascii_alphanumeric() {
+        format!("b'{}'", byte as char)
+    } else {
+        format!("0x{:02x}", byte)
+    }
+}
+
+// a match pattern for a set of bytes, e.g. b'a'..=b'f' | b'z'
+fn byte_pattern(symbols: &[u8]) -> String {
+    byte_runs(symbols).iter()
+        .map(|&(first, last)| match last - first {
+            0 => byte_literal(first),
+            1 => format!("{} | {}", byte_literal(first), byte_literal(last)),
+            _ => format!("{}..={}", byte_literal(first), byte_literal(last)),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+impl DFA {
+    // standalone rust source for `pub fn matches(input: &[u8]) -> Option<usize>`, which returns the length of
+    // the longest accepted prefix just like DFA::longest_match. needs nothing from this crate to compile
+    pub fn to_rust(&self, style: RustStyle) -> String {
+        let mut output = format!(
+            "// generated from a {} state DFA over {} symbols. do not edit, regenerate it from the transition table\n\n",
+            self.states.len(),
+            self.len_alphabet
+        );
+        match style {
+            RustStyle::Match => self.write_match(&mut output),
+            RustStyle::Table => self.write_table(&mut output),
+        }
+        output
+    }
+
+    fn write_match(&self, output: &mut String) {
+        let symbols = self.column_symbols();
+        let start = if self.states[self.start_state].accepting { "Some(0)" } else { "None" };
+        let accepting: Vec<String> = self.states.iter().filter(|s| s.accepting).map(|s| s.state_id.to_string()).collect();
+
+        // every byte that leads from one state to another, grouped into one arm per pair
+        let mut arms: BTreeMap<(u32, u32), Vec<u8>> = BTreeMap::new();
+        for state in &self.states {
+            for (column, transition) in state.transitions.iter().enumerate() {
+                if let Some(to) = transition {
+                    arms.entry((state.state_id, *to)).or_default().push(symbols[column]);
+                }
+            }
+        }
+
+        output.push_str("pub fn matches(input: &[u8]) -> Option<usize> {\n");
+        // nothing can ever get past the start state (or ever accept), so the answer doesn't depend on input
+        if arms.is_empty() || accepting.is_empty() {
+            output.push_str("    let _ = input;\n");
+            output.push_str(&format!("    {}\n", start));
+            output.push_str("}\n");
+            return;
+        }
+
+        output.push_str(&format!("    let mut state: u32 = {};\n", self.start_state));
+        output.push_str(&format!("    let mut longest = {};\n", start));
+        output.push_str("    for (i, &byte) in input.iter().enumerate() {\n");
+        output.push_str("        state = match (state, byte) {\n");
+        for ((from, to), bytes) in &arms {
+            output.push_str(&format!("            ({}, {}) => {},\n", from, byte_pattern(bytes), to));
+        }
+        output.push_str("            _ => break,\n");
+        output.push_str("        };\n");
+        output.push_str(&format!("        if matches!(state, {}) {{\n", accepting.join(" | ")));
+        output.push_str("            longest = Some(i + 1);\n");
+        output.push_str("        }\n");
+        output.push_str("    }\n");
+        output.push_str("    longest\n");
+        output.push_str("}\n");
+    }
+
+    fn write_table(&self, output: &mut String) {
+        let symbols = self.column_symbols();
+        let mut columns: Vec<String> = vec!["NO_COLUMN".to_string(); 256];
+        for (column, &byte) in symbols.iter().enumerate() {
+            columns[byte as usize] = column.to_string();
+        }
+
+        output.push_str("// E in the transition table\nconst DEAD: u32 = u32::MAX;\n");
+        output.push_str("// a byte that is not in the alphabet\nconst NO_COLUMN: u16 = u16::MAX;\n\n");
+
+        output.push_str("// the column of each byte\n");
+        output.push_str("static COLUMN: [u16; 256] = [\n");
+        for row in columns.chunks(8) {
+            output.push_str(&format!("    {},\n", row.join(", ")));
+        }
+        output.push_str("];\n\n");
+
+        output.push_str(&format!("static ACCEPTING: [bool; {}] = [\n", self.states.len()));
+        for row in self.states.chunks(16) {
+            let row: Vec<String> = row.iter().map(|s| s.accepting.to_string()).collect();
+            output.push_str(&format!("    {},\n", row.join(", ")));
+        }
+        output.push_str("];\n\n");
+
+        output.push_str(&format!("static NEXT: [[u32; {}]; {}] = [\n", self.len_alphabet, self.states.len()));
+        for state in &self.states {
+            let row: Vec<String> = state.transitions.iter()
+                .map(|t| t.map_or("DEAD".to_string(), |t| t.to_string()))
+                .collect();
+            output.push_str(&format!("    [{}],\n", row.join(", ")));
+        }
+        output.push_str("];\n\n");
+
+        output.push_str("pub fn matches(input: &[u8]) -> Option<usize> {\n");
+        output.push_str(&format!("    let mut state = {};\n", self.start_state));
+        output.push_str(&format!("    let mut longest = if ACCEPTING[{0}] {{ Some(0) }} else {{ None }};\n", self.start_state));
+        output.push_str("    for (i, &byte) in input.iter().enumerate() {\n");
+        output.push_str("        let column = COLUMN[byte as usize];\n");
+        output.push_str("        if column == NO_COLUMN {\n");
+        output.push_str("            break;\n");
+        output.push_str("        }\n");
+        output.push_str("        let next = NEXT[state][column as usize];\n");
+        output.push_str("        if next == DEAD {\n");
+        output.push_str("            break;\n");
+        output.push_str("        }\n");
+        output.push_str("        state = next as usize;\n");
+        output.push_str("        if ACCEPTING[state] {\n");
+        output.push_str("            longest = Some(i + 1);\n");
+        output.push_str("        }\n");
+        output.push_str("    }\n");
+        output.push_str("    longest\n");
+        output.push_str("}\n");
+    }
+}
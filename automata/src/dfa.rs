@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use crate::error::{Error, Result};
+use crate::hopcroft;
+
+#[derive(Debug, Clone)] // debug lets you print StateRow with :? for debugging purposes. clone lets you deep copy
+pub struct StateRow {
+    pub accepting: bool,
+    pub state_id: u32,
+    pub transitions: Vec<Option<u32>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DFA {
+    pub states: Vec<StateRow>,
+    pub len_alphabet: usize,
+    pub self_is_accepting: bool,
+    pub matching: bool,
+    pub will_not_match: bool,
+    pub current_state: usize,
+    // row index of the start state
+    pub start_state: usize,
+    // the id each row had in the table it was loaded from. rows are always indexed 0..n internally
+    pub original_ids: Vec<u32>,
+    pub longest_accepting_match: Vec<u8>,
+    pub current_read_sequence: Vec<u8>,
+    pub alphabet: HashMap<u8, usize>
+}
+
+// the byte each column stands for. tables loaded without an alphabet (DFAMINIMIZE) just use the column number
+pub(crate) fn symbols_by_column(alphabet: &HashMap<u8, usize>, width: usize) -> Vec<u8> {
+    let mut symbols: Vec<u8> = (0..width).map(|c| c as u8).collect();
+    for (&byte, &column) in alphabet {
+        if column < symbols.len() {
+            symbols[column] = byte;
+        }
+    }
+    symbols
+}
+
+// which ids DFA::print_with writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateIds {
+    // the row indexes every algorithm works with
+    Dense,
+    // the ids from the loaded table. after minimizing, each state is named after one of the states merged into it
+    Original,
+}
+
+// which minimization DFA::minimize_with runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimizeAlgorithm {
+    // partition refinement, builds the new table in one pass. both algorithms drop unreachable and dead states
+    Hopcroft,
+    // the original stack of (states, remaining symbols) that merges states one at a time, now run in rounds
+    // until nothing splits. kept around so the two can be cross checked, both print the same table
+    StackRefinement,
+}
+
+// impl block for dfa minimize
+impl DFA {
+    // Create dfa from rows. the start state is state 0, or the first row if no state has id 0
+    pub fn new(states: Vec<StateRow>, alphabet: HashMap<u8, usize>) -> Result<Self> {
+        let start = match states.iter().find(|s| s.state_id == 0) {
+            Some(_) => 0,
+            None => states.first().map_or(0, |s| s.state_id),
+        };
+        Self::with_start_state(states, alphabet, start)
+    }
+
+    // Create dfa from rows whose ids can be in any order and have gaps. the rows are renumbered to 0..n in the
+    // order given, transitions follow along, and the old ids are kept in original_ids
+    pub fn with_start_state(mut states: Vec<StateRow>, alphabet: HashMap<u8, usize>, start: u32) -> Result<Self> {
+        if states.is_empty() {
+            return Err(Error::EmptyAutomaton);
+        }
+
+        let original_ids: Vec<u32> = states.iter().map(|s| s.state_id).collect();
+        let already_dense = original_ids.iter().enumerate().all(|(i, &id)| id == i as u32);
+        if already_dense {
+            // nothing to renumber, only make sure every transition lands on a row
+            let len = states.len() as u32;
+            if let Some(&state) = states.iter().flat_map(|s| s.transitions.iter().flatten()).find(|&&t| t >= len) {
+                return Err(Error::UnknownState { state });
+            }
+        } else {
+            let mut dense: HashMap<u32, u32> = HashMap::with_capacity(states.len());
+            for (i, &id) in original_ids.iter().enumerate() {
+                if dense.insert(id, i as u32).is_some() {
+                    return Err(Error::DuplicateState { state: id });
+                }
+            }
+            for (i, state) in states.iter_mut().enumerate() {
+                state.state_id = i as u32;
+                for transition in state.transitions.iter_mut().flatten() {
+                    *transition = *dense.get(transition).ok_or(Error::UnknownState { state: *transition })?;
+                }
+            }
+        }
+        let start_state = original_ids.iter().position(|&id| id == start).ok_or(Error::UnknownState { state: start })?;
+
+        let len_alphabet = states[0].transitions.len(); 
+        let self_is_accepting = states[start_state].accepting;
+        let matching = true;
+        let will_not_match = false;
+        let current_state = start_state;
+        let longest_accepting_match = Vec::new();
+        let current_read_sequence = Vec::new();
+
+        Ok(DFA {
+            states,
+            len_alphabet,
+            self_is_accepting,
+            matching,
+            will_not_match,
+            current_state,
+            start_state,
+            original_ids,
+            longest_accepting_match,
+            current_read_sequence,
+            alphabet
+        })
+    }
+
+    // Get state from ID: Reference
+    pub fn get_state(&self, id: u32) -> Option<&StateRow> {
+        self.states.get(id as usize)
+    }
+
+    //
+    pub fn size(&self) -> usize {
+        self.states.len()
+    }
+
+    // the byte each column of the table reads
+    pub fn column_symbols(&self) -> Vec<u8> {
+        symbols_by_column(&self.alphabet, self.len_alphabet)
+    }
+
+    // see if a state is an accepting state
+    pub fn state_is_accepting(&self, id: u32) -> bool {
+        match self.get_state(id) {
+            Some(state) => state.accepting,
+            None => false,
+        }
+    }
+
+    // see if the dfa is currently in an accept state (for simulation)
+    pub fn self_is_accepting(&self) -> bool {
+        self.self_is_accepting
+    }
+
+    pub fn get_current_state_id(&self) -> usize {
+        self.current_state
+    }
+
+    pub fn set_current_state(&mut self, state_id: u32) {
+        self.current_state = state_id as usize
+    }
+
+    pub fn get_longest_accepting_match(&self) -> Vec<u8> {
+        self.longest_accepting_match.clone()
+    }
+
+    pub fn remove_state(&mut self, state_to_remove: usize, state_to_keep: Option<usize>) {
+        // remove the row from the transition table
+        self.states.remove(state_to_remove);
+        self.original_ids.remove(state_to_remove);
+
+        // the start state moves along with the other ids
+        if self.start_state == state_to_remove {
+            self.start_state = state_to_keep.map_or(0, |k| if k > state_to_remove { k - 1 } else { k });
+        } else if self.start_state > state_to_remove {
+            self.start_state -= 1;
+        }
+
+        // Fix state IDs after removal. we need to shift the states so we don't try to access an index out of bounds after removing an earlier state
+        for (i, state) in self.states.iter_mut().enumerate() {
+            state.state_id = i as u32;
+
+            for transition in &mut state.transitions {
+                // remove references to the state we just removed
+                if *transition == Some(state_to_remove as u32) {
+                    // if we have a state to keep, point to it; else set to None
+                    *transition = state_to_keep.map(|k| k as u32);
+                } else if let Some(t) = transition {
+                    // decrement state IDs larger than removed index
+                    if *t > state_to_remove as u32 {
+                        *t -= 1;
+                    }
+                }
+            }
+
+        }
+    }
+
+    // private: states should only be merged if they are found to be equivalent in the minimize algorithm
+    fn merge_states(&mut self, state1: u32, state2: u32) {
+        // println!("Result of merging states: {} and {}", state1, state2); // DEBUG
+
+        // always choose state2 to remove. It's fine to choose state 1 but it doesn't matter which
+        let state_to_remove = state2 as usize;
+        let state_to_keep = state1 as usize;
+
+        // Give state 1 all of state 2's in-transitions (if the table has state2, change it to state1)
+        for state in &mut self.states {
+            for transition in &mut state.transitions {
+                if *transition == Some(state_to_remove as u32) {
+                    *transition = Some(state_to_keep as u32);
+                }
+            }
+        }
+        
+        self.remove_state(state_to_remove, Some(state_to_keep));
+        // println!("{}", self.print()); // DEBUG
+    }
+
+    pub fn minimize(&mut self) -> Vec<Option<u32>> {
+        self.minimize_with(MinimizeAlgorithm::Hopcroft)
+    }
+
+    // minimize, then canonicalize so the same language always prints the same table whichever algorithm ran
+    // returns the state each old state ended up in, indexed by old row. None for states that were dropped
+    // because nothing reaches them or nothing can be accepted from them
+    pub fn minimize_with(&mut self, algorithm: MinimizeAlgorithm) -> Vec<Option<u32>> {
+        let merged = match algorithm {
+            MinimizeAlgorithm::Hopcroft => self.minimize_hopcroft(),
+            MinimizeAlgorithm::StackRefinement => self.minimize_stack_refinement(),
+        };
+        let renumbered = self.canonicalize();
+        merged.into_iter().map(|s| s.map(|s| renumbered[s as usize])).collect()
+    }
+
+    // renumber the states in breadth first order from the start state, following columns in alphabet order.
+    // two minimal DFAs for the same language over the same columns then print identically, so plain diff can
+    // compare them. states the start state can't reach keep their relative order at the end
+    // returns the new id of each state, indexed by its old row
+    pub fn canonicalize(&mut self) -> Vec<u32> {
+        let mut order: Vec<usize> = Vec::with_capacity(self.states.len());
+        let mut new_id = vec![u32::MAX; self.states.len()];
+        new_id[self.start_state] = 0;
+        order.push(self.start_state);
+        let mut next = 0;
+        while next < order.len() {
+            let s = order[next];
+            next += 1;
+            for t in self.states[s].transitions.iter().flatten() {
+                if new_id[*t as usize] == u32::MAX {
+                    new_id[*t as usize] = order.len() as u32;
+                    order.push(*t as usize);
+                }
+            }
+        }
+        for (s, id) in new_id.iter_mut().enumerate() {
+            if *id == u32::MAX {
+                *id = order.len() as u32;
+                order.push(s);
+            }
+        }
+
+        self.states = order.iter().enumerate().map(|(id, &s)| StateRow {
+            accepting: self.states[s].accepting,
+            state_id: id as u32,
+            transitions: self.states[s].transitions.iter().map(|t| t.map(|t| new_id[t as usize])).collect(),
+        }).collect();
+        self.original_ids = order.iter().map(|&s| self.original_ids[s]).collect();
+        self.start_state = 0;
+        self.current_state = 0;
+        self.self_is_accepting = self.states[0].accepting;
+        new_id
+    }
+
+    // Hopcroft's algorithm. E entries go to an implicit dead state so the table is complete while refining,
+    // then the class holding the dead state turns back into E. unreachable states never make it into the new table
+    fn minimize_hopcroft(&mut self) -> Vec<Option<u32>> {
+        let k = self.len_alphabet;
+
+        // reachable states in the order we find them, so the start state becomes 0
+        let mut reachable: Vec<usize> = Vec::new();
+        let mut dense = vec![usize::MAX; self.states.len()];
+        let mut queue = VecDeque::from([self.start_state]);
+        dense[self.start_state] = 0;
+        reachable.push(self.start_state);
+        while let Some(s) = queue.pop_front() {
+            for t in self.states[s].transitions.iter().flatten() {
+                let t = *t as usize;
+                if dense[t] == usize::MAX {
+                    dense[t] = reachable.len();
+                    reachable.push(t);
+                    queue.push_back(t);
+                }
+            }
+        }
+
+        // complete table over the reachable states plus the dead state at the end
+        let dead = reachable.len();
+        let mut accepting: Vec<bool> = reachable.iter().map(|&s| self.states[s].accepting).collect();
+        accepting.push(false);
+        let mut delta = Vec::with_capacity((dead + 1) * k);
+        for &s in &reachable {
+            delta.extend(self.states[s].transitions.iter().map(|t| t.map_or(dead, |t| dense[t as usize])));
+        }
+        delta.extend(std::iter::repeat_n(dead, k));
+
+        let class = hopcroft::coarsest_partition(&accepting, &delta, k);
+
+        // number the classes in the order their first state was reached. the dead class becomes E
+        let dead_class = class[dead];
+        let mut new_id = vec![u32::MAX; dead + 1];
+        let mut representatives: Vec<usize> = Vec::new();
+        for s in 0..dead {
+            if class[s] != dead_class && new_id[class[s]] == u32::MAX {
+                new_id[class[s]] = representatives.len() as u32;
+                representatives.push(s);
+            }
+        }
+
+        // where each old state went: unreachable ones and the ones in the dead class are gone
+        let mut merged: Vec<Option<u32>> = dense.iter()
+            .map(|&d| (d != usize::MAX && class[d] != dead_class).then(|| new_id[class[d]]))
+            .collect();
+
+        // the whole language is empty: still need a start state to print
+        if representatives.is_empty() {
+            self.states = vec![StateRow { accepting: false, state_id: 0, transitions: vec![None; k] }];
+            self.original_ids = vec![self.original_ids[self.start_state]];
+            merged[self.start_state] = Some(0);
+        } else {
+            // each new state keeps the original id of the first state reached in its class
+            self.original_ids = representatives.iter().map(|&s| self.original_ids[reachable[s]]).collect();
+            self.states = representatives.iter().enumerate().map(|(id, &s)| StateRow {
+                accepting: accepting[s],
+                state_id: id as u32,
+                transitions: delta[s * k..(s + 1) * k].iter()
+                    .map(|&t| if class[t] == dead_class { None } else { Some(new_id[class[t]]) })
+                    .collect(),
+            }).collect();
+        }
+
+        self.start_state = 0;
+        self.current_state = 0;
+        self.self_is_accepting = self.states[0].accepting;
+        merged
+    }
+
+    // drop every state the start state can't reach and every state that can't reach an accepting one. edges into
+    // those become E. the start state always stays, even when the language is empty
+    // returns the row each old state moved to, None if it was dropped
+    fn trim(&mut self) -> Vec<Option<u32>> {
+        let useful = self.useful();
+        let mut moved: Vec<Option<u32>> = vec![None; self.states.len()];
+        let mut kept: Vec<usize> = Vec::new();
+        for s in 0..self.states.len() {
+            if useful[s] || s == self.start_state {
+                moved[s] = Some(kept.len() as u32);
+                kept.push(s);
+            }
+        }
+
+        self.states = kept.iter().enumerate().map(|(id, &s)| StateRow {
+            accepting: self.states[s].accepting,
+            state_id: id as u32,
+            // a start state that is only kept to have one is as dead as the rest, so edges into it go too
+            transitions: self.states[s].transitions.iter()
+                .map(|t| t.filter(|&t| useful[t as usize]).and_then(|t| moved[t as usize]))
+                .collect(),
+        }).collect();
+        self.original_ids = kept.iter().map(|&s| self.original_ids[s]).collect();
+        self.start_state = moved[self.start_state].unwrap() as usize;
+        self.current_state = self.start_state;
+        moved
+    }
+
+    // the original stack of (states, remaining symbols), run in rounds: each round splits every class of the
+    // last one by which class each symbol leads to, until a round splits nothing. trimming first and refining
+    // by class rather than by exact destination gives the same minimal DFA as Hopcroft, just slower
+    fn minimize_stack_refinement(&mut self) -> Vec<Option<u32>> {
+        let trimmed = self.trim();
+
+        // represents the columns in the transition table. All the symbols we need to look at
+        let alphabet: Vec<usize> = (0..self.len_alphabet).collect();
+
+        // initial partition: split accepting and nonaccepting as they will never be equivalent
+        let mut class: Vec<usize> = self.states.iter().map(|s| s.accepting as usize).collect();
+        let mut classes: Vec<Vec<u32>> = [false, true].iter()
+            .map(|&accepting| self.states.iter().filter(|s| s.accepting == accepting).map(|s| s.state_id).collect::<Vec<u32>>())
+            .filter(|group| !group.is_empty())
+            .collect();
+
+        loop {
+            // L = stack of (state_set, alphabet)
+            let mut stack: Vec<(Vec<u32>, Vec<usize>)> = classes.iter().map(|group| (group.clone(), alphabet.clone())).collect();
+            let mut refined: Vec<Vec<u32>> = Vec::new();
+
+            // iterate over combinations of states transitioning on certain symbols
+            while let Some((states, mut chars)) = stack.pop() {
+                // consider one symbol of the alphabet. once they have all been looked at the set can't split further
+                let Some(c) = chars.pop() else {
+                    refined.push(states);
+                    continue;
+                };
+
+                // partitions: states whose transition on c lands in the same class (or is E for all of them)
+                let mut partitions: HashMap<Option<usize>, Vec<u32>> = HashMap::new();
+                for s in &states {
+                    let dest = self.states[*s as usize].transitions[c].map(|t| class[t as usize]);
+                    partitions.entry(dest).or_default().push(*s);
+                }
+
+                for (_, group) in partitions {
+                    // a single state is already a class of its own, the rest get revisited with the next symbol
+                    if group.len() == 1 {
+                        refined.push(group);
+                    } else {
+                        stack.push((group, chars.clone()));
+                    }
+                }
+            }
+
+            let stable = refined.len() == classes.len();
+            for (i, group) in refined.iter().enumerate() {
+                for &s in group {
+                    class[s as usize] = i;
+                }
+            }
+            classes = refined;
+            if stable {
+                break;
+            }
+        }
+
+        // M = sets to merge
+        let mut merge_sets: Vec<Vec<u32>> = classes.into_iter().filter(|group| group.len() > 1).collect();
+
+        // where each trimmed state is now
+        let mut merged: Vec<Option<u32>> = (0..self.states.len() as u32).map(Some).collect();
+
+        // Merge the partition sets with multiple states (equivalent)
+        // we do this in descending order because as we remove states, we want to avoid index out of bounds errors
+        for i in 0..merge_sets.len() {
+            let mut sorted_set = merge_sets[i].clone();
+            sorted_set.sort_unstable_by(|a, b| b.cmp(a)); // descending order within a merge set
+
+            let first = sorted_set.pop().unwrap(); // keep the larger
+            for &s in &sorted_set {
+                self.merge_states(first, s);
+                for state in merged.iter_mut().flatten() {
+                    if *state == s {
+                        *state = first;
+                    }
+                    if *state > s {
+                        *state -= 1;
+                    }
+                }
+
+                // After merging s, all remaining IDs > s need to be decremented in all sets. This sucks
+                for later_set in merge_sets.iter_mut().skip(i + 1) {
+                    *later_set = later_set.iter().map(|id| if *id > s { id - 1 } else { *id }).collect();
+                }
+            }
+        }
+
+        trimmed.into_iter().map(|t| t.and_then(|t| merged[t as usize])).collect()
+    }
+
+    // simulate a single step of the dfa simulation and return the current read string
+    // need to manipulate current state, possibly longest accepted string, matching, and self_is_accepting
+    pub fn simulate_one_step(&mut self, symbol: u8) -> Result<()> {
+        // println!("-SIMULATE ONE STEP");
+        // println!("\tI am trying to transition on {}", symbol);
+        // println!("\tState {} transitions len: {}, symbol index: {}", self.get_current_state_id(), self.states[self.get_current_state_id()].transitions.len(), symbol);
+
+        // get the index of the symbol to look in the state transitions
+        let symbol_index = match self.alphabet.get(&symbol) {
+            Some(i) => *i,
+            None => return Err(Error::UnknownSymbol { symbol, offset: self.current_read_sequence.len() }),
+        };
+
+        // attempt to transition
+        let next_state = self.states[self.current_state].transitions[symbol_index];
+        
+        // there is a transition
+        if let Some (next) = next_state {
+            self.current_state = next as usize;
+
+            // append the thing we just read to our read sequence
+            self.current_read_sequence.push(symbol);
+
+            // check acceptance
+            if self.states[self.current_state].accepting {
+                self.self_is_accepting = true;
+                self.longest_accepting_match = self.current_read_sequence.clone();
+            }
+        }
+        else { // if no transition, we failed to read anything new and we cannot continue
+            self.will_not_match = true;
+        }
+        // println!("\tMY current read sequence is {}", self.current_read_sequence);
+        // println!("\t My longest accepting read sequence is {}", self.longest_accepting_match);
+        // println!("\t My current sequence is longer than my longest accepting: {}", self.current_read_sequence.len() > self.longest_accepting_match.len());
+        // println!("\t I am in an accepting state: {}", self.self_is_accepting);
+        Ok(())
+    }
+
+    // simulate an entire string on the dfa and return whether it was accepted or not
+    pub fn simulate(&mut self, seq: &[u8]) -> Result<bool> {
+        // println!("-SIMULATE CALLED ON {:?}-", seq);
+        // we don't exclude whitespace
+
+        // make sure we are starting in the start state
+        self.current_state = self.start_state;
+        self.self_is_accepting = false;
+        self.matching = true;
+        self.will_not_match = false;
+        // this needs to be reset to see how far the simulation got before it read something it couldnt accept
+        self.longest_accepting_match = Vec::new();
+        self.current_read_sequence = Vec::new();
+
+        // run on all symbols until we finish or cannot continue
+        for &symbol in seq {
+            if self.will_not_match {
+                // println!("\tI couldn't keep reading :/");
+                break; // we cannot transition from current state
+            }
+            // println!("\tI am capable of reading a symbol.");
+            self.simulate_one_step(symbol)?;
+        }
+        // println!("\tSIMULATE: No more symbols. I ended in a match: {} and my longest match was {}", self.self_is_accepting, self.longest_accepting_match);
+        
+        // return if any prefix was accepted. Is this gonna work?
+        Ok(!self.longest_accepting_match.is_empty())
+    }
+
+    // length of the longest prefix of input the dfa accepts, Some(0) if only the empty string. None if nothing
+    // does. takes &self and never allocates, so one scanner definition can be shared between threads
+    // a byte outside the alphabet stops the walk just like an E entry
+    pub fn longest_match(&self, input: &[u8]) -> Option<usize> {
+        let mut state = self.start_state;
+        let mut longest = if self.states[state].accepting { Some(0) } else { None };
+
+        for (i, symbol) in input.iter().enumerate() {
+            let next = self.alphabet.get(symbol).and_then(|&column| self.states[state].transitions[column]);
+            match next {
+                Some(next) => state = next as usize,
+                None => break,
+            }
+            if self.states[state].accepting {
+                longest = Some(i + 1);
+            }
+        }
+        longest
+    }
+
+    // print function
+    pub fn print(&self) -> String {
+        self.print_with(StateIds::Dense)
+    }
+
+    // print the table with either the row indexes or the ids the rows were loaded with
+    pub fn print_with(&self, ids: StateIds) -> String {
+        let name = |id: u32| match ids {
+            StateIds::Dense => id,
+            StateIds::Original => self.original_ids[id as usize],
+        };
+        let mut output = String::new();
+
+        for state in &self.states {
+            if self.state_is_accepting(state.state_id) {
+                output.push_str("+ ");
+            } else {
+                output.push_str("- ");
+            }
+
+            output.push_str(&name(state.state_id).to_string());
+            output.push(' ');
+
+            for transition in &state.transitions {
+                match transition {
+                    Some(t) => output.push_str(&name(*t).to_string()),
+                    None => output.push('E'),
+                }
+                output.push(' ');
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+}
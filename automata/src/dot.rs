@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+use crate::alphabetencoding;
+use crate::codegen::byte_runs;
+use crate::dfa::DFA;
+use crate::nfa::NFA;
+
+// fill colours for state groups, reused in order once they run out
+const PALETTE: [&str; 12] = [
+    "lightblue", "lightpink", "palegreen", "khaki", "plum", "lightsalmon",
+    "paleturquoise", "wheat", "thistle", "lightcoral", "lightcyan", "navajowhite",
+];
+
+// how one symbol shows up on an edge. same as the alphabet encoding, plus hex for anything that means
+// something inside a [a-f] class or a dot string
+fn symbol_label(symbol: u8) -> String {
+    match symbol {
+        b'-' | b'[' | b']' | b'"' => format!("x{:02x}", symbol),
+        _ => alphabetencoding::encode(&[symbol]),
+    }
+}
+
+// all the symbols on one edge as a single label. runs of 3 or more consecutive bytes become ranges like [a-f]
+fn class_label(symbols: &[u8]) -> String {
+    let runs = byte_runs(symbols);
+    if let [(first, last)] = runs[..] {
+        if first == last {
+            return symbol_label(first);
+        }
+    }
+
+    let mut label = String::from("[");
+    for (first, last) in runs {
+        if last - first >= 2 {
+            label.push_str(&format!("{}-{}", symbol_label(first), symbol_label(last)));
+        } else {
+            for s in first..=last {
+                label.push_str(&symbol_label(s));
+            }
+        }
+    }
+    label.push(']');
+    label
+}
+
+// shared layout for both automata. edges maps (from, to) to the labels of every symbol between them
+fn render(name: &str, start: u32, nodes: &[String], edges: &BTreeMap<(u32, u32), Vec<u8>>) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("digraph {} {{\n", name));
+    output.push_str("    rankdir=LR;\n");
+    output.push_str("    __start [shape=point];\n");
+    for node in nodes {
+        output.push_str(&format!("    {}\n", node));
+    }
+    output.push_str(&format!("    __start -> {};\n", start));
+
+    for ((from, to), symbols) in edges {
+        output.push_str(&format!("    {} -> {} [label=\"{}\"];\n", from, to, class_label(symbols)));
+    }
+    output.push_str("}\n");
+    output
+}
+
+fn node(id: u32, accepting: bool, attributes: &str) -> String {
+    let shape = if accepting { "doublecircle" } else { "circle" };
+    format!("{} [shape={}{}];", id, shape, attributes)
+}
+
+impl DFA {
+    // graphviz version of the table. parallel edges are merged into one class label
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with_groups(&[])
+    }
+
+    // same as to_dot, but states that share a group get the same fill colour. None (or a state past the end
+    // of groups) is left unfilled. DFAMINIMIZE uses this to show which original states were merged
+    pub fn to_dot_with_groups(&self, groups: &[Option<usize>]) -> String {
+        let symbols = self.column_symbols();
+
+        let nodes: Vec<String> = self.states.iter().map(|state| {
+            let fill = match groups.get(state.state_id as usize).copied().flatten() {
+                Some(group) => format!(", style=filled, fillcolor={}", PALETTE[group % PALETTE.len()]),
+                None => String::new(),
+            };
+            node(state.state_id, state.accepting, &fill)
+        }).collect();
+
+        let mut edges: BTreeMap<(u32, u32), Vec<u8>> = BTreeMap::new();
+        for state in &self.states {
+            for (column, transition) in state.transitions.iter().enumerate() {
+                if let Some(to) = transition {
+                    edges.entry((state.state_id, *to)).or_default().push(symbols[column]);
+                }
+            }
+        }
+
+        render("DFA", self.start_state as u32, &nodes, &edges)
+    }
+}
+
+impl NFA {
+    // graphviz version of the table, one edge per destination in each cell
+    pub fn to_dot(&self) -> String {
+        let symbols = self.column_symbols();
+
+        let nodes: Vec<String> = self.states.iter().map(|state| node(state.state_id, state.accepting, "")).collect();
+
+        let mut edges: BTreeMap<(u32, u32), Vec<u8>> = BTreeMap::new();
+        for state in &self.states {
+            for (column, destinations) in state.transitions.iter().enumerate() {
+                for to in destinations {
+                    edges.entry((state.state_id, *to)).or_default().push(symbols[column]);
+                }
+            }
+        }
+
+        render("NFA", self.start_state_id, &nodes, &edges)
+    }
+}
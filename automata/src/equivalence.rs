@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use crate::dfa::DFA;
+
+// a state from each table. None is the dead state behind an E
+type Pair = (Option<usize>, Option<usize>);
+
+impl DFA {
+    // Ok if both DFAs accept the same language, else a shortest string that exactly one of them accepts
+    pub fn equivalent(&self, other: &DFA) -> Result<(), Vec<u8>> {
+        match shortest_difference(self, other, (Some(self.start_state), Some(other.start_state))) {
+            Some(counterexample) => Err(counterexample),
+            None => Ok(()),
+        }
+    }
+
+    // whether a counterexample from equivalent is accepted, starting from the start state. a table without an
+    // alphabet reads each symbol as a column number
+    pub fn accepts_counterexample(&self, counterexample: &[u8]) -> bool {
+        let mut state = Some(self.start_state);
+        for symbol in counterexample {
+            let column = if self.alphabet.is_empty() { Some(*symbol as usize) } else { self.alphabet.get(symbol).copied() };
+            state = step(self, state, column);
+        }
+        state.is_some_and(|s| self.states[s].accepting)
+    }
+
+    // a shortest suffix that is accepted from exactly one of the two states, None if they are equivalent
+    // in a minimal DFA every pair of states has one, that's why they weren't merged
+    pub fn distinguishing_suffix(&self, a: usize, b: usize) -> Option<Vec<u8>> {
+        shortest_difference(self, self, (Some(a), Some(b)))
+    }
+
+    // for each of our states, the state of other that the same strings lead to (None if no string reaches it,
+    // or other is already dead there). if other is the minimized version of this DFA that is exactly
+    // the state each original state got merged into
+    pub fn state_correspondence(&self, other: &DFA) -> Vec<Option<u32>> {
+        let symbols = paired_columns(self, other);
+
+        let mut correspondence = vec![None; self.states.len()];
+        let start: Pair = (Some(self.start_state), Some(other.start_state));
+        let mut seen: HashSet<Pair> = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(pair) = queue.pop_front() {
+            if let (Some(s), Some(t)) = pair {
+                correspondence[s].get_or_insert(t as u32);
+            }
+            for &(_, column_a, column_b) in &symbols {
+                let next = (step(self, pair.0, column_a), step(other, pair.1, column_b));
+                if next.0.is_some() && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        correspondence
+    }
+}
+
+// a shortest string that leads exactly one of the two DFAs from its half of start to an accepting state
+// walks the product of the two tables breadth first, with None standing in for the dead state behind an E
+fn shortest_difference(a: &DFA, b: &DFA, start: Pair) -> Option<Vec<u8>> {
+    let symbols = paired_columns(a, b);
+    let accepting = |dfa: &DFA, state: Option<usize>| state.is_some_and(|s| dfa.states[s].accepting);
+
+    // every pair we have seen, with the pair and symbol we came from so the string can be rebuilt
+    let mut parent: HashMap<Pair, Option<(Pair, u8)>> = HashMap::new();
+    parent.insert(start, None);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(pair) = queue.pop_front() {
+        if accepting(a, pair.0) != accepting(b, pair.1) {
+            let mut difference = Vec::new();
+            let mut current = pair;
+            while let Some(&Some((previous, symbol))) = parent.get(&current) {
+                difference.push(symbol);
+                current = previous;
+            }
+            difference.reverse();
+            return Some(difference);
+        }
+
+        for &(symbol, column_a, column_b) in &symbols {
+            let next = (step(a, pair.0, column_a), step(b, pair.1, column_b));
+            // both dead: nothing past here can tell them apart
+            if next == (None, None) || parent.contains_key(&next) {
+                continue;
+            }
+            parent.insert(next, Some((pair, symbol)));
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+// one transition from a state of the product walk. missing states, columns and E entries all lead to the dead state
+fn step(dfa: &DFA, state: Option<usize>, column: Option<usize>) -> Option<usize> {
+    match (state, column) {
+        (Some(s), Some(c)) => dfa.states[s].transitions.get(c).copied().flatten().map(|t| t as usize),
+        _ => None,
+    }
+}
+
+// line up the columns of two tables by the byte they read, sorted by byte so counterexamples come out smallest first
+// a byte only one of them knows about sends the other to the dead state
+pub(crate) fn paired_columns(a: &DFA, b: &DFA) -> Vec<(u8, Option<usize>, Option<usize>)> {
+    if a.alphabet.is_empty() || b.alphabet.is_empty() {
+        // at least one table has no alphabet, so columns can only be matched up by position
+        let symbols = if a.alphabet.is_empty() { b.column_symbols() } else { a.column_symbols() };
+        let width = a.len_alphabet.max(b.len_alphabet);
+        let mut paired: Vec<(u8, Option<usize>, Option<usize>)> = (0..width)
+            .map(|c| (
+                symbols.get(c).copied().unwrap_or(c as u8),
+                (c < a.len_alphabet).then_some(c),
+                (c < b.len_alphabet).then_some(c),
+            ))
+            .collect();
+        paired.sort_by_key(|p| p.0);
+        return paired;
+    }
+
+    let mut bytes: Vec<u8> = a.alphabet.keys().chain(b.alphabet.keys()).copied().collect();
+    bytes.sort_unstable();
+    bytes.dedup();
+    bytes.into_iter()
+        .map(|byte| (byte, a.alphabet.get(&byte).copied(), b.alphabet.get(&byte).copied()))
+        .collect()
+}
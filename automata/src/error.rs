@@ -0,0 +1,110 @@
+use std::fmt;
+use std::io;
+
+// one problem found while loading a transition table, pointing at the token that caused it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub message: String,
+}
+
+// every way the library can fail. the binaries print these and pick the exit code
+#[derive(Debug)]
+pub enum Error {
+    // a file we needed could not be opened or read
+    MissingFile { path: String, source: io::Error },
+    // a transition table or scanner definition row that doesn't fit the expected format
+    MalformedRow { file: String, line: usize, message: String },
+    // a transition table with one or more bad rows. every problem is listed, not just the first
+    MalformedTable { file: String, diagnostics: Vec<Diagnostic> },
+    // a grammar line that isn't `A -> ...` or `| ...`
+    MalformedGrammar { file: String, line: usize, message: String },
+    // a regex that doesn't parse. column is 1 based, counted in bytes of the pattern. expected lists what
+    // would have been fine there, empty when the problem isn't a wrong token
+    MalformedRegex { pattern: String, column: usize, message: String, expected: Vec<String> },
+    // an alphabet encoded token with a bad x escape or a literal that should have been escaped
+    BadEscape { token: String, offset: usize },
+    // the automaton was asked to read a byte that isn't in its alphabet
+    UnknownSymbol { symbol: u8, offset: usize },
+    // a transition or start state that names a state the table doesn't have
+    UnknownState { state: u32 },
+    // two rows of a table with the same state id
+    DuplicateState { state: u32 },
+    // no token recognizer matched anything at this position of the source
+    NoMatch { line: usize, column: usize },
+    // encode was handed nothing to encode
+    EmptyToken,
+    // a DFA or NFA was built from zero states
+    EmptyAutomaton,
+    // two automata had to read the same alphabet for an operation and don't
+    AlphabetMismatch { message: String },
+    // a compiled automaton file that is cut short or doesn't hold what its header says
+    MalformedBinary { message: String },
+    // a compiled automaton file written by a different version of the format
+    UnsupportedVersion { found: u16, supported: u16 },
+    // reading or writing a stream (stdin, stdout, a socket...) failed partway through
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingFile { path, source } => write!(f, "could not open file '{}': {}", path, source),
+            Error::MalformedRow { file, line, message } => write!(f, "{}:{}: malformed row: {}", file, line, message),
+            Error::MalformedTable { file, diagnostics } => {
+                // one file:line:column per problem so editors can jump to each of them
+                for (i, d) in diagnostics.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}:{}:{}: {}", file, d.line, d.column, d.message)?;
+                    if !d.token.is_empty() {
+                        write!(f, " (read '{}')", d.token)?;
+                    }
+                }
+                Ok(())
+            }
+            Error::MalformedGrammar { file, line, message } => write!(f, "{}:{}: malformed grammar: {}", file, line, message),
+            Error::MalformedRegex { pattern, column, message, expected } => {
+                write!(f, "bad regex '{}' at column {}: {}", pattern, column, message)?;
+                match expected.len() {
+                    0 => Ok(()),
+                    1 => write!(f, ", expected {}", expected[0]),
+                    _ => write!(f, ", expected one of {}", expected.join(", ")),
+                }
+            }
+            Error::BadEscape { token, offset } => write!(f, "bad escape in token '{}' at offset {}", token, offset),
+            Error::UnknownSymbol { symbol, offset } => write!(f, "symbol 0x{:02x} at offset {} is not in the alphabet", symbol, offset),
+            Error::UnknownState { state } => write!(f, "state {} is not in the table", state),
+            Error::DuplicateState { state } => write!(f, "state {} is defined more than once", state),
+            Error::NoMatch { line, column } => write!(f, "no token was found at line {} col {}", line, column),
+            Error::EmptyToken => write!(f, "cannot encode an empty token"),
+            Error::EmptyAutomaton => write!(f, "automaton must contain at least one state"),
+            Error::AlphabetMismatch { message } => write!(f, "alphabets do not match: {}", message),
+            Error::MalformedBinary { message } => write!(f, "bad compiled automaton: {}", message),
+            Error::UnsupportedVersion { found, supported } => {
+                write!(f, "compiled automaton is format version {}, this build reads version {}", found, supported)
+            }
+            Error::Io(source) => write!(f, "i/o error: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::MissingFile { source, .. } | Error::Io(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,171 @@
+// Hopcroft partition refinement. works on a complete table: delta[state * num_symbols + symbol] is the next state
+// (callers add an explicit dead state for the E entries). gives back the class of every state, where two states
+// share a class exactly when they are equivalent. runs in O(k n log n)
+pub(crate) fn coarsest_partition(accepting: &[bool], delta: &[usize], num_symbols: usize) -> Vec<usize> {
+    let n = accepting.len();
+    let mut partition = Partition::new(accepting);
+    if n == 0 || num_symbols == 0 {
+        return partition.class;
+    }
+
+    // inverse transitions in one flat array: the states that go to t on c are
+    // sources[offsets[c * n + t]..offsets[c * n + t + 1]]
+    let mut offsets = vec![0usize; num_symbols * n + 1];
+    for s in 0..n {
+        for c in 0..num_symbols {
+            offsets[c * n + delta[s * num_symbols + c] + 1] += 1;
+        }
+    }
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+    let mut sources = vec![0usize; num_symbols * n];
+    let mut fill = offsets.clone();
+    for s in 0..n {
+        for c in 0..num_symbols {
+            let slot = c * n + delta[s * num_symbols + c];
+            sources[fill[slot]] = s;
+            fill[slot] += 1;
+        }
+    }
+
+    // every starting class is a splitter
+    let mut worklist: Vec<usize> = (0..partition.num_classes()).collect();
+    let mut in_worklist = vec![true; partition.num_classes()];
+    let mut splitter: Vec<usize> = Vec::new();
+    let mut preimage: Vec<usize> = Vec::new();
+
+    while let Some(a) = worklist.pop() {
+        in_worklist[a] = false;
+        // snapshot the splitter, it may get split itself while we go through the symbols
+        splitter.clear();
+        splitter.extend_from_slice(partition.members(a));
+
+        for c in 0..num_symbols {
+            preimage.clear();
+            for &t in &splitter {
+                preimage.extend_from_slice(&sources[offsets[c * n + t]..offsets[c * n + t + 1]]);
+            }
+            if preimage.is_empty() {
+                continue;
+            }
+
+            for (old, new) in partition.split(&preimage) {
+                in_worklist.push(false);
+                if in_worklist[old] {
+                    // both halves still need to be used as splitters
+                    worklist.push(new);
+                    in_worklist[new] = true;
+                } else {
+                    // the other half is implied by the old splitter, so only the smaller one is needed
+                    let smaller = if partition.len(new) <= partition.len(old) { new } else { old };
+                    worklist.push(smaller);
+                    in_worklist[smaller] = true;
+                }
+            }
+        }
+    }
+
+    partition.class
+}
+
+// refinable partition: each class is a contiguous range of elements, with marked states moved to the front
+struct Partition {
+    elements: Vec<usize>,
+    location: Vec<usize>,
+    class: Vec<usize>,
+    first: Vec<usize>,
+    end: Vec<usize>,
+    mid: Vec<usize>,
+}
+
+impl Partition {
+    // starts as accepting vs not accepting, leaving out whichever of the two is empty
+    fn new(accepting: &[bool]) -> Self {
+        let mut elements: Vec<usize> = (0..accepting.len()).filter(|&s| accepting[s]).collect();
+        let num_accepting = elements.len();
+        elements.extend((0..accepting.len()).filter(|&s| !accepting[s]));
+
+        let mut bounds = Vec::new();
+        if num_accepting > 0 {
+            bounds.push((0, num_accepting));
+        }
+        if num_accepting < elements.len() {
+            bounds.push((num_accepting, elements.len()));
+        }
+
+        let mut location = vec![0; elements.len()];
+        let mut class = vec![0; elements.len()];
+        for (c, &(first, end)) in bounds.iter().enumerate() {
+            for (i, &s) in elements.iter().enumerate().take(end).skip(first) {
+                location[s] = i;
+                class[s] = c;
+            }
+        }
+
+        Partition {
+            elements,
+            location,
+            class,
+            first: bounds.iter().map(|b| b.0).collect(),
+            end: bounds.iter().map(|b| b.1).collect(),
+            mid: bounds.iter().map(|b| b.0).collect(),
+        }
+    }
+
+    fn num_classes(&self) -> usize {
+        self.first.len()
+    }
+
+    fn len(&self, c: usize) -> usize {
+        self.end[c] - self.first[c]
+    }
+
+    fn members(&self, c: usize) -> &[usize] {
+        &self.elements[self.first[c]..self.end[c]]
+    }
+
+    // split every class that the marked states only partly cover. the marked part becomes the new class
+    // gives back (old class, new class) for each split
+    fn split(&mut self, marked: &[usize]) -> Vec<(usize, usize)> {
+        let mut touched = Vec::new();
+        for &s in marked {
+            let c = self.class[s];
+            let i = self.location[s];
+            // already marked (a state can show up twice in a preimage)
+            if i < self.mid[c] {
+                continue;
+            }
+            if self.mid[c] == self.first[c] {
+                touched.push(c);
+            }
+            // swap s into the marked region at the front of its class
+            let j = self.mid[c];
+            let other = self.elements[j];
+            self.elements.swap(i, j);
+            self.location[other] = i;
+            self.location[s] = j;
+            self.mid[c] += 1;
+        }
+
+        let mut splits = Vec::new();
+        for c in touched {
+            if self.mid[c] == self.end[c] {
+                // every state was marked, nothing to split
+                self.mid[c] = self.first[c];
+                continue;
+            }
+
+            let new = self.first.len();
+            self.first.push(self.first[c]);
+            self.end.push(self.mid[c]);
+            self.mid.push(self.first[c]);
+            for i in self.first[c]..self.mid[c] {
+                self.class[self.elements[i]] = new;
+            }
+            self.first[c] = self.mid[c];
+            splits.push((c, new));
+        }
+        splits
+    }
+}
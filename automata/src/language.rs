@@ -0,0 +1,303 @@
+use std::collections::VecDeque;
+
+use crate::alphabetencoding;
+use crate::dfa::DFA;
+
+// accepted strings in shortlex order: shorter first, then by byte. see DFA::enumerate
+pub struct Enumerate<'a> {
+    dfa: &'a DFA,
+    // (byte, column), sorted by byte
+    symbols: Vec<(u8, usize)>,
+    // exact[k][s]: some string of exactly k symbols leads from s to an accepting state. grows one length
+    // at a time as the walk gets there
+    exact: Vec<Vec<bool>>,
+    max_len: usize,
+    // the length being enumerated right now
+    length: usize,
+    // depth first walk over strings of exactly `length`: (state, next symbol to try) for each prefix
+    stack: Vec<(usize, usize)>,
+    path: Vec<u8>,
+}
+
+impl Enumerate<'_> {
+    // the next symbol from state, starting at symbol index `from`, after which acceptance is exactly
+    // `remaining - 1` more steps away. every branch taken ends in an accepted string, so the walk never backs
+    // out of a dead end
+    fn next_child(&self, state: usize, from: usize, remaining: usize) -> Option<(usize, usize)> {
+        (from..self.symbols.len()).find_map(|i| {
+            let to = self.dfa.states[state].transitions[self.symbols[i].1]? as usize;
+            self.exact[remaining - 1][to].then_some((i, to))
+        })
+    }
+
+    // add exact[k] for the next k, from exact[k - 1]
+    fn extend_exact(&mut self) {
+        let previous = self.exact.last().unwrap();
+        let next = self.dfa.states.iter()
+            .map(|state| state.transitions.iter().flatten().any(|&to| previous[to as usize]))
+            .collect();
+        self.exact.push(next);
+    }
+}
+
+impl Iterator for Enumerate<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let Some(&(state, from)) = self.stack.last() else {
+                // done with this length, on to the next one
+                if self.length >= self.max_len {
+                    return None;
+                }
+                self.length += 1;
+                self.extend_exact();
+                // nothing of this length is accepted, don't even start the walk
+                if self.exact[self.length][self.dfa.start_state] {
+                    self.stack.push((self.dfa.start_state, 0));
+                }
+                continue;
+            };
+
+            if self.path.len() == self.length {
+                let accepted = self.dfa.states[state].accepting;
+                let token = accepted.then(|| alphabetencoding::encode(&self.path));
+                self.stack.pop();
+                self.path.pop();
+                if token.is_some() {
+                    return token;
+                }
+                continue;
+            }
+
+            let remaining = self.length - self.path.len();
+            match self.next_child(state, from, remaining) {
+                Some((i, to)) => {
+                    self.stack.last_mut().unwrap().1 = i + 1;
+                    self.stack.push((to, 0));
+                    self.path.push(self.symbols[i].0);
+                }
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                }
+            }
+        }
+    }
+}
+
+impl DFA {
+    // fewest symbols from each state to an accepting one, by breadth first search over the reversed table
+    fn distance_to_accepting(&self) -> Vec<usize> {
+        let mut reverse: Vec<Vec<usize>> = vec![Vec::new(); self.states.len()];
+        for (from, state) in self.states.iter().enumerate() {
+            for to in state.transitions.iter().flatten() {
+                reverse[*to as usize].push(from);
+            }
+        }
+
+        let mut distance = vec![usize::MAX; self.states.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for (s, state) in self.states.iter().enumerate() {
+            if state.accepting {
+                distance[s] = 0;
+                queue.push_back(s);
+            }
+        }
+        while let Some(s) = queue.pop_front() {
+            for &from in &reverse[s] {
+                if distance[from] == usize::MAX {
+                    distance[from] = distance[s] + 1;
+                    queue.push_back(from);
+                }
+            }
+        }
+        distance
+    }
+
+    // every accepted string of at most max_len symbols, alphabet encoded, shortest first and then by byte
+    // lazy, so .take(n) on a huge language only does the work for n strings: only branches that end in an
+    // accepted string of exactly the length being enumerated are walked. a length nothing is accepted at still
+    // costs one pass over the table
+    pub fn enumerate(&self, max_len: usize) -> Enumerate<'_> {
+        let mut symbols: Vec<(u8, usize)> = self.column_symbols().into_iter().enumerate().map(|(c, b)| (b, c)).collect();
+        symbols.sort_unstable();
+        Enumerate {
+            dfa: self,
+            symbols,
+            exact: vec![self.states.iter().map(|state| state.accepting).collect()],
+            max_len,
+            length: 0,
+            stack: vec![(self.start_state, 0)],
+            path: Vec::new(),
+        }
+    }
+
+    // how many strings of each length 0..=len the DFA accepts. counts past u128::MAX stay at u128::MAX
+    pub fn count_accepted(&self, len: usize) -> Vec<u128> {
+        // ways[s] = number of strings of the current length that end in state s
+        let mut ways = vec![0u128; self.states.len()];
+        ways[self.start_state] = 1;
+
+        let mut counts = Vec::with_capacity(len + 1);
+        for n in 0..=len {
+            counts.push(self.states.iter().zip(&ways)
+                .filter(|(state, _)| state.accepting)
+                .fold(0u128, |total, (_, &w)| total.saturating_add(w)));
+            if n == len {
+                break;
+            }
+
+            let mut next = vec![0u128; self.states.len()];
+            for (s, state) in self.states.iter().enumerate() {
+                if ways[s] == 0 {
+                    continue;
+                }
+                for to in state.transitions.iter().flatten() {
+                    next[*to as usize] = next[*to as usize].saturating_add(ways[s]);
+                }
+            }
+            ways = next;
+        }
+        counts
+    }
+}
+
+impl DFA {
+    // states the start state can reach, in breadth first order
+    fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.states.len()];
+        reachable[self.start_state] = true;
+        let mut queue = VecDeque::from([self.start_state]);
+        while let Some(s) = queue.pop_front() {
+            for to in self.states[s].transitions.iter().flatten() {
+                if !reachable[*to as usize] {
+                    reachable[*to as usize] = true;
+                    queue.push_back(*to as usize);
+                }
+            }
+        }
+        reachable
+    }
+
+    // states on some path from the start state to an accepting state. only these matter for the language
+    pub(crate) fn useful(&self) -> Vec<bool> {
+        let distance = self.distance_to_accepting();
+        self.reachable().into_iter().zip(distance).map(|(r, d)| r && d != usize::MAX).collect()
+    }
+
+    // accepts nothing at all, not even the empty string
+    pub fn is_empty(&self) -> bool {
+        self.distance_to_accepting()[self.start_state] == usize::MAX
+    }
+
+    // accepts finitely many strings: no cycle between the start state and an accepting state
+    pub fn is_finite(&self) -> bool {
+        let useful = self.useful();
+
+        // iterative depth first search over the useful states. reaching a state still on the stack is a cycle
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark { New, OnStack, Done }
+        let mut mark = vec![Mark::New; self.states.len()];
+        for root in (0..self.states.len()).filter(|&s| useful[s]) {
+            if mark[root] != Mark::New {
+                continue;
+            }
+            mark[root] = Mark::OnStack;
+            let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+            while let Some(&mut (s, ref mut column)) = stack.last_mut() {
+                if *column == self.len_alphabet {
+                    mark[s] = Mark::Done;
+                    stack.pop();
+                    continue;
+                }
+                let next = self.states[s].transitions[*column];
+                *column += 1;
+                if let Some(to) = next.map(|t| t as usize).filter(|&t| useful[t]) {
+                    match mark[to] {
+                        Mark::OnStack => return false,
+                        Mark::New => {
+                            mark[to] = Mark::OnStack;
+                            stack.push((to, 0));
+                        }
+                        Mark::Done => {}
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    // accepts every string over its alphabet: every reachable state accepts and none of them has an E
+    pub fn is_universal(&self) -> bool {
+        self.reachable().into_iter().zip(&self.states)
+            .filter(|(reachable, _)| *reachable)
+            .all(|(_, state)| state.accepting && state.transitions.iter().all(|t| t.is_some()))
+    }
+
+    pub fn accepts_empty_string(&self) -> bool {
+        self.states[self.start_state].accepting
+    }
+
+    // the first accepted string in shortlex order (fewest symbols, then smallest bytes), None if the language is empty
+    pub fn shortest_accepted(&self) -> Option<Vec<u8>> {
+        let distance = self.distance_to_accepting();
+        if distance[self.start_state] == usize::MAX {
+            return None;
+        }
+        // stepping to the smallest byte that is still one closer to acceptance keeps it shortest and smallest
+        self.walk_down(|s| distance[s])
+    }
+
+    // the longest accepted string, smallest bytes first on ties. None if the language is empty or infinite
+    pub fn longest_accepted(&self) -> Option<Vec<u8>> {
+        if self.is_empty() || !self.is_finite() {
+            return None;
+        }
+        let useful = self.useful();
+
+        // longest[s] = most symbols from s to an accepting state. the useful part is acyclic, so memoized
+        // depth first search (done iteratively) gives it in one pass
+        let mut longest: Vec<Option<usize>> = vec![None; self.states.len()];
+        let mut stack: Vec<usize> = vec![self.start_state];
+        while let Some(&s) = stack.last() {
+            let pending: Vec<usize> = self.states[s].transitions.iter().flatten()
+                .map(|&t| t as usize)
+                .filter(|&t| useful[t] && longest[t].is_none())
+                .collect();
+            if !pending.is_empty() {
+                stack.extend(pending);
+                continue;
+            }
+            stack.pop();
+            let through = self.states[s].transitions.iter().flatten()
+                .filter(|&&t| useful[t as usize])
+                .filter_map(|&t| longest[t as usize])
+                .map(|l| l + 1)
+                .max();
+            let own = self.states[s].accepting.then_some(0);
+            longest[s] = through.max(own);
+        }
+        self.walk_down(|s| longest[s].map_or(usize::MAX, |l| l))
+    }
+
+    // follow the smallest byte whose target has goal one less than the current state, until goal is 0 and the
+    // state accepts. goal is how many symbols are left to an accepting state, usize::MAX for no way
+    fn walk_down(&self, goal: impl Fn(usize) -> usize) -> Option<Vec<u8>> {
+        let mut symbols: Vec<(u8, usize)> = self.column_symbols().into_iter().enumerate().map(|(c, b)| (b, c)).collect();
+        symbols.sort_unstable();
+
+        let mut state = self.start_state;
+        let mut string = Vec::new();
+        while goal(state) > 0 {
+            let remaining = goal(state);
+            let (byte, to) = symbols.iter().find_map(|&(byte, column)| {
+                let to = self.states[state].transitions[column]? as usize;
+                (goal(to) == remaining - 1).then_some((byte, to))
+            })?;
+            string.push(byte);
+            state = to;
+        }
+        self.states[state].accepting.then_some(string)
+    }
+}
@@ -0,0 +1,22 @@
+// shared code for the compiler projects: transition table DFAs and NFAs plus the alphabet encoding
+// LUTHOR, NFAMATCH, DFAMINIMIZE and ALPHABETENCODING all build on this so a fix here reaches every tool
+pub mod alphabetencoding;
+pub mod binary;
+pub mod cli;
+pub mod codegen;
+pub mod dfa;
+mod dot;
+mod equivalence;
+pub mod error;
+mod hopcroft;
+pub mod language;
+pub mod nfa;
+pub mod nfafile;
+mod product;
+pub mod regex;
+pub mod random;
+pub mod stream;
+pub mod tokenfile;
+pub mod ttfile;
+
+pub use error::Error;
@@ -0,0 +1,279 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use crate::dfa::{StateRow, DFA};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone)] // debug lets you print NfaStateRow with :? for debugging purposes. clone lets you deep copy
+pub struct NfaStateRow {
+    pub accepting: bool,
+    pub state_id: u32,
+    pub transitions: Vec<HashSet<u32>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NFA {
+    pub states: Vec<NfaStateRow>,
+    pub len_alphabet: usize,
+    pub matching: bool,
+    pub will_not_match: bool,
+    pub current_states: HashSet<u32>,
+    pub longest_accepting_match: Vec<u8>,
+    pub current_read_sequence: Vec<u8>,
+    pub alphabet: HashMap<u8, usize>,
+    pub start_state_id: u32,
+    // the column of lambda (epsilon) moves, always the last one. None if the table has no lambda column
+    pub lambda_column: Option<usize>,
+}
+
+// impl block for Nfa
+impl NFA {
+    // Create Nfa from rows. a table with one more column than the alphabet has symbols keeps lambda moves in
+    // that last column. without an alphabet every column is a symbol
+    pub fn new(states: Vec<NfaStateRow>, alphabet: HashMap<u8, usize>, start: u32) -> Result<Self> {
+        if states.is_empty() {
+            return Err(Error::EmptyAutomaton);
+        }
+        // rows are numbered 0..n, so anything past the last row isn't a state
+        if start as usize >= states.len() {
+            return Err(Error::UnknownState { state: start });
+        }
+
+        let len_alphabet = states[0].transitions.len();
+        let matching = true;
+        let will_not_match = false;
+        let current_states = HashSet::new();
+        let longest_accepting_match = Vec::new();
+        let current_read_sequence = Vec::new();
+        let start_state_id = start;
+        let lambda_column = (!alphabet.is_empty() && len_alphabet == alphabet.len() + 1).then(|| len_alphabet - 1);
+
+        Ok(NFA {
+            states,
+            len_alphabet,
+            matching,
+            will_not_match,
+            current_states,
+            longest_accepting_match,
+            current_read_sequence,
+            alphabet,
+            start_state_id,
+            lambda_column
+        })
+    }
+
+    // Get state from ID: Reference
+    pub fn get_state(&self, id: u32) -> Option<&NfaStateRow> {
+        self.states.get(id as usize)
+    }
+
+    //
+    pub fn size(&self) -> usize {
+        self.states.len()
+    }
+
+    // the byte each column of the table reads
+    pub fn column_symbols(&self) -> Vec<u8> {
+        crate::dfa::symbols_by_column(&self.alphabet, self.len_alphabet)
+    }
+
+    // TODO
+    // see if a state is an accepting state
+    pub fn state_is_accepting(&self, id: u32) -> bool {
+        match self.get_state(id) {
+            Some(state) => state.accepting,
+            None => false,
+        }
+    }
+
+   pub fn is_accepting(&self) -> bool {
+        self.current_states.iter().any(|&id| self.state_is_accepting(id))
+    }
+
+    pub fn get_current_states_id(&self) -> HashSet<u32> {
+        self.current_states.clone()
+    }
+
+    pub fn set_current_states(&mut self, state_ids: HashSet<u32>) {
+        self.current_states = state_ids
+    }
+
+    pub fn get_longest_accepting_match(&self) -> Vec<u8> {
+        self.longest_accepting_match.clone()
+    }
+
+    // simulate a single step of the nfa: every current state moves on symbol, then the lambda closure of
+    // wherever they went. need to manipulate current states, possibly longest accepted string and will_not_match
+    pub fn simulate_one_step(&mut self, symbol: u8) -> Result<()> {
+        // get the index of the symbol to look in the state transitions
+        let symbol_index = match self.alphabet.get(&symbol) {
+            Some(i) => *i,
+            None => return Err(Error::UnknownSymbol { symbol, offset: self.current_read_sequence.len() }),
+        };
+
+        // attempt to transition
+        let next_states = self.follow_lambda(&self.follow_char(&self.current_states, symbol_index));
+
+        // there is a transition
+        if !next_states.is_empty() {
+            self.current_states = next_states;
+
+            // append the thing we just read to our read sequence
+            self.current_read_sequence.push(symbol);
+
+            // check acceptance
+            if self.is_accepting() {
+                self.longest_accepting_match = self.current_read_sequence.clone();
+            }
+        }
+        else { // if no transition, we failed to read anything new and we cannot continue
+            self.will_not_match = true;
+        }
+        Ok(())
+    }
+
+    // simulate an entire string on the nfa and return whether some nonempty prefix was accepted, like DFA::simulate
+    pub fn simulate(&mut self, seq: &[u8]) -> Result<bool> {
+        // we don't exclude whitespace
+
+        // start in everything the start state reaches by lambda alone
+        self.current_states = self.follow_lambda(&HashSet::from([self.start_state_id]));
+
+        self.matching = true;
+        self.will_not_match = false;
+
+        // this needs to be reset to see how far the simulation got before it read something it couldnt accept
+        self.longest_accepting_match = Vec::new();
+        self.current_read_sequence = Vec::new();
+
+        // run on all symbols until we finish or cannot continue
+        for &symbol in seq {
+            if self.will_not_match {
+                break; // no current state can read anything more
+            }
+            self.simulate_one_step(symbol)?;
+        }
+
+        Ok(!self.longest_accepting_match.is_empty())
+    }
+
+    // length of the longest prefix of input the nfa accepts, Some(0) if only the empty string. None if nothing
+    // does. same answer as DFA::longest_match on the subset construction, without building it
+    // a byte outside the alphabet stops the walk just like running out of states
+    pub fn longest_match(&self, input: &[u8]) -> Option<usize> {
+        let accepting = |states: &HashSet<u32>| states.iter().any(|&id| self.state_is_accepting(id));
+        let mut states = self.follow_lambda(&HashSet::from([self.start_state_id]));
+        let mut longest = accepting(&states).then_some(0);
+
+        for (i, symbol) in input.iter().enumerate() {
+            let Some(&column) = self.alphabet.get(symbol) else {
+                break;
+            };
+            states = self.follow_lambda(&self.follow_char(&states, column));
+            if states.is_empty() {
+                break;
+            }
+            if accepting(&states) {
+                longest = Some(i + 1);
+            }
+        }
+        longest
+    }
+
+    // print function
+    pub fn print(&self) -> String {
+        let mut output = String::new();
+
+        for state in &self.states {
+            if self.state_is_accepting(state.state_id) {
+                output.push_str("+ ");
+            } else {
+                output.push_str("- ");
+            }
+
+            output.push_str(&state.state_id.to_string());
+            output.push(' ');
+
+            for transition in &state.transitions {
+                if transition.is_empty() {
+                    output.push_str("E ");
+                } else {
+                    let mut ids: Vec<u32> = transition.iter().cloned().collect();
+                    ids.sort();
+                    let s: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                    output.push_str(&s.join(","));
+                    output.push(' ');
+                }
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+impl NFA {
+    // every state reachable from states by lambda moves alone, states included
+    pub fn follow_lambda(&self, states: &HashSet<u32>) -> HashSet<u32> {
+        let mut closure = states.clone();
+        let Some(lambda) = self.lambda_column else {
+            return closure;
+        };
+        let mut stack: Vec<u32> = states.iter().copied().collect();
+        while let Some(state) = stack.pop() {
+            for &next in &self.states[state as usize].transitions[lambda] {
+                if closure.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        closure
+    }
+
+    // the states one move on column gets to from states, without following lambda afterwards
+    pub fn follow_char(&self, states: &HashSet<u32>, column: usize) -> HashSet<u32> {
+        states.iter().flat_map(|&state| self.states[state as usize].transitions[column].iter().copied()).collect()
+    }
+
+    // the equivalent DFA, built from the lambda closures of sets of NFA states. states are numbered in breadth
+    // first order from the start and the empty set becomes E. also returns the NFA states behind each DFA state
+    pub fn subset_construction(&self) -> (DFA, Vec<BTreeSet<u32>>) {
+        let symbol_columns: Vec<usize> = (0..self.len_alphabet).filter(|&c| Some(c) != self.lambda_column).collect();
+        let closure = |states: &HashSet<u32>| -> BTreeSet<u32> { self.follow_lambda(states).into_iter().collect() };
+
+        let start = closure(&HashSet::from([self.start_state_id]));
+        let mut sets: Vec<BTreeSet<u32>> = vec![start.clone()];
+        let mut ids: HashMap<BTreeSet<u32>, u32> = HashMap::from([(start, 0)]);
+        let mut rows: Vec<StateRow> = Vec::new();
+        let mut queue = VecDeque::from([0usize]);
+
+        while let Some(id) = queue.pop_front() {
+            let current: HashSet<u32> = sets[id].iter().copied().collect();
+            let mut transitions = Vec::with_capacity(symbol_columns.len());
+            for &column in &symbol_columns {
+                let next = closure(&self.follow_char(&current, column));
+                if next.is_empty() {
+                    transitions.push(None);
+                    continue;
+                }
+                let next_id = *ids.entry(next.clone()).or_insert_with(|| {
+                    sets.push(next);
+                    queue.push_back(sets.len() - 1);
+                    (sets.len() - 1) as u32
+                });
+                transitions.push(Some(next_id));
+            }
+            rows.push(StateRow {
+                accepting: current.iter().any(|&state| self.state_is_accepting(state)),
+                state_id: id as u32,
+                transitions,
+            });
+        }
+
+        // the lambda column is last, so the symbol columns keep their numbers and the alphabet carries over
+        let dfa = DFA::new(rows, self.alphabet.clone()).expect("subset states are dense and in range");
+        (dfa, sets)
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::alphabetencoding;
+use crate::error::{Diagnostic, Error, Result};
+use crate::nfa::NFA;
+use crate::ttfile::{fields, parse_nfa_table};
+
+// an NFA definition file (NFAMATCH's input). line 1 is `<state count> <lambda> <alphabet>`, every symbol
+// alphabet encoded, then one `+/- id cell cell ...` row per state like a tt file. the columns follow the
+// alphabet in the order it was written and the lambda column comes last. state 0 is the start state
+#[derive(Debug, Clone)]
+pub struct NfaFile {
+    pub nfa: NFA,
+    // the character that stands for lambda. it is never part of the alphabet
+    pub lambda: u8,
+}
+
+struct Header {
+    count: usize,
+    lambda: u8,
+    symbols: Vec<u8>,
+}
+
+// line 1. every problem goes into diagnostics, None if there was any
+fn parse_header(line: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<Header> {
+    let start = diagnostics.len();
+    let mut report = |column: usize, token: &str, message: String| {
+        diagnostics.push(Diagnostic { line: 1, column, token: token.to_string(), message });
+    };
+
+    let fields = fields(line);
+    if fields.len() < 3 {
+        report(1, line.trim(), "expected the state count, the lambda character and the alphabet".to_string());
+        return None;
+    }
+
+    let (count_column, count) = fields[0];
+    let count = count.parse::<usize>().map_err(|_| report(count_column, count, "expected the number of states".to_string())).ok();
+
+    let (lambda_column, lambda) = fields[1];
+    let lambda = match alphabetencoding::decode(lambda) {
+        Ok(bytes) if bytes.len() == 1 => Some(bytes[0]),
+        _ => {
+            report(lambda_column, lambda, "lambda must be a single alphabet encoded character".to_string());
+            None
+        }
+    };
+
+    // like a scan.u alphabet, one field can hold several symbols
+    let mut symbols: Vec<u8> = Vec::new();
+    for &(column, token) in &fields[2..] {
+        let Ok(bytes) = alphabetencoding::decode(token) else {
+            report(column, token, "bad alphabet encoding".to_string());
+            continue;
+        };
+        for b in bytes {
+            if Some(b) == lambda {
+                report(column, token, "the lambda character can't also be in the alphabet".to_string());
+            } else if symbols.contains(&b) {
+                report(column, token, format!("{} is in the alphabet twice", alphabetencoding::encode(&[b])));
+            } else {
+                symbols.push(b);
+            }
+        }
+    }
+
+    if diagnostics.len() > start {
+        return None;
+    }
+    Some(Header { count: count?, lambda: lambda?, symbols })
+}
+
+pub fn parse_nfa_file(file: &str, contents: &str) -> Result<NfaFile> {
+    let (first_line, rest) = contents.split_once('\n').unwrap_or((contents, ""));
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let header = parse_header(first_line, &mut diagnostics);
+
+    // the table leaves blank lines alone, so an empty first line keeps its line numbers matching the file
+    let columns = header.as_ref().map(|h| h.symbols.len() + 1);
+    let rows = match parse_nfa_table(file, &format!("\n{}", rest), columns) {
+        Ok(rows) => Some(rows),
+        Err(Error::MalformedTable { diagnostics: table, .. }) => {
+            diagnostics.extend(table);
+            None
+        }
+        Err(e) => return Err(e),
+    };
+
+    if let (Some(header), Some(rows)) = (&header, &rows) {
+        if header.count != rows.len() {
+            let (column, token) = fields(first_line)[0];
+            diagnostics.push(Diagnostic {
+                line: 1,
+                column,
+                token: token.to_string(),
+                message: format!("the header says {} states but the table has {}", header.count, rows.len()),
+            });
+        }
+    }
+
+    match (header, rows) {
+        (Some(header), Some(rows)) if diagnostics.is_empty() => {
+            let alphabet: HashMap<u8, usize> = header.symbols.iter().enumerate().map(|(column, &b)| (b, column)).collect();
+            Ok(NfaFile { nfa: NFA::new(rows, alphabet, 0)?, lambda: header.lambda })
+        }
+        _ => {
+            diagnostics.sort_by_key(|d| (d.line, d.column));
+            Err(Error::MalformedTable { file: file.to_string(), diagnostics })
+        }
+    }
+}
+
+pub fn load_nfa_file(path: &str) -> Result<NfaFile> {
+    let contents = fs::read_to_string(path).map_err(|e| Error::MissingFile { path: path.to_string(), source: e })?;
+    parse_nfa_file(path, &contents)
+}
+
+impl NfaFile {
+    // the file again, header and table. symbols are written one per field
+    pub fn print(&self) -> String {
+        let mut header = vec![self.nfa.size().to_string(), alphabetencoding::encode(&[self.lambda])];
+        let symbols = self.nfa.column_symbols();
+        let symbol_columns = self.nfa.lambda_column.unwrap_or(self.nfa.len_alphabet);
+        header.extend(symbols[..symbol_columns].iter().map(|&b| alphabetencoding::encode(&[b])));
+        format!("{}\n{}", header.join(" "), self.nfa.print())
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::dfa::{StateRow, DFA};
+use crate::error::{Error, Result};
+
+// which boolean operation a product DFA computes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl Operation {
+    fn accepts(self, left: bool, right: bool) -> bool {
+        match self {
+            Operation::Union => left || right,
+            Operation::Intersection => left && right,
+            Operation::Difference => left && !right,
+        }
+    }
+
+    // a pair that can never accept again. None is the dead state behind an E and it never leaves
+    fn is_dead(self, left: Option<usize>, right: Option<usize>) -> bool {
+        match self {
+            Operation::Union => left.is_none() && right.is_none(),
+            Operation::Intersection => left.is_none() || right.is_none(),
+            Operation::Difference => left.is_none(),
+        }
+    }
+}
+
+impl DFA {
+    // strings either DFA accepts
+    pub fn union(&self, other: &DFA) -> Result<DFA> {
+        self.product(other, Operation::Union)
+    }
+
+    // strings both DFAs accept
+    pub fn intersect(&self, other: &DFA) -> Result<DFA> {
+        self.product(other, Operation::Intersection)
+    }
+
+    // strings this DFA accepts and other doesn't, e.g. identifiers minus keywords
+    pub fn difference(&self, other: &DFA) -> Result<DFA> {
+        self.product(other, Operation::Difference)
+    }
+
+    // every string over the alphabet this DFA rejects. E entries go to a new sink state first so they flip too
+    pub fn complement(&self) -> DFA {
+        let sink = self.states.len() as u32;
+        let needs_sink = self.states.iter().any(|s| s.transitions.iter().any(|t| t.is_none()));
+
+        let mut states: Vec<StateRow> = self.states.iter().enumerate()
+            .map(|(id, s)| StateRow {
+                accepting: !s.accepting,
+                state_id: id as u32,
+                transitions: s.transitions.iter().map(|t| Some(t.unwrap_or(sink))).collect(),
+            })
+            .collect();
+        if needs_sink {
+            states.push(StateRow { accepting: true, state_id: sink, transitions: vec![Some(sink); self.len_alphabet] });
+        }
+
+        self.with_table(states, self.start_state as u32)
+    }
+
+    // a new DFA with this one's alphabet and a different table
+    fn with_table(&self, states: Vec<StateRow>, start: u32) -> DFA {
+        // never empty, every caller builds at least the start state
+        DFA::with_start_state(states, self.alphabet.clone(), start).expect("table has a start state")
+    }
+
+    // for each of our columns, the column of other that reads the same byte
+    fn matching_columns(&self, other: &DFA) -> Result<Vec<usize>> {
+        let mismatch = |message: String| Err(Error::AlphabetMismatch { message });
+
+        if self.alphabet.is_empty() != other.alphabet.is_empty() {
+            return mismatch("only one of the DFAs has an alphabet".to_string());
+        }
+        if self.len_alphabet != other.len_alphabet {
+            return mismatch(format!("{} columns vs {} columns", self.len_alphabet, other.len_alphabet));
+        }
+        // no alphabets (e.g. tables from DFAMINIMIZE): columns line up by position
+        if self.alphabet.is_empty() {
+            return Ok((0..self.len_alphabet).collect());
+        }
+
+        let mut columns = vec![0; self.len_alphabet];
+        for (byte, &column) in &self.alphabet {
+            match other.alphabet.get(byte) {
+                Some(&other_column) if column < columns.len() => columns[column] = other_column,
+                Some(_) => {}
+                None => return mismatch(format!("symbol 0x{:02x} is only in the first alphabet", byte)),
+            }
+        }
+        if let Some(byte) = other.alphabet.keys().find(|b| !self.alphabet.contains_key(b)) {
+            return mismatch(format!("symbol 0x{:02x} is only in the second alphabet", byte));
+        }
+        Ok(columns)
+    }
+
+    // product construction over the reachable pairs. pairs that can't accept anymore become E
+    fn product(&self, other: &DFA, operation: Operation) -> Result<DFA> {
+        let columns = self.matching_columns(other)?;
+        let step = |dfa: &DFA, state: Option<usize>, column: usize| {
+            state.and_then(|s| dfa.states[s].transitions.get(column).copied().flatten()).map(|t| t as usize)
+        };
+
+        let start = (Some(self.start_state), Some(other.start_state));
+        let mut ids: HashMap<(Option<usize>, Option<usize>), u32> = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        let mut states: Vec<StateRow> = Vec::new();
+
+        while let Some((left, right)) = queue.pop_front() {
+            let accepting = operation.accepts(
+                left.is_some_and(|s| self.states[s].accepting),
+                right.is_some_and(|s| other.states[s].accepting),
+            );
+
+            let mut transitions = Vec::with_capacity(self.len_alphabet);
+            for (column, &other_column) in columns.iter().enumerate() {
+                let next = (step(self, left, column), step(other, right, other_column));
+                if operation.is_dead(next.0, next.1) {
+                    transitions.push(None);
+                    continue;
+                }
+                // ids are handed out in the order pairs are queued, so they match the row each pair ends up in
+                let next_id = match ids.get(&next) {
+                    Some(&id) => id,
+                    None => {
+                        let id = ids.len() as u32;
+                        ids.insert(next, id);
+                        queue.push_back(next);
+                        id
+                    }
+                };
+                transitions.push(Some(next_id));
+            }
+
+            states.push(StateRow { accepting, state_id: states.len() as u32, transitions });
+        }
+
+        Ok(self.with_table(states, 0))
+    }
+}
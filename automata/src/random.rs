@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dfa::{StateRow, DFA};
+use crate::nfa::{NfaStateRow, NFA};
+
+// splitmix64. tiny and dependency free, and the same seed gives the same automaton on every machine
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0..n. n must be above 0
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // true with probability p, clamped to 0..=1
+    pub fn chance(&mut self, p: f64) -> bool {
+        // the top 53 bits as a float in [0, 1)
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < p
+    }
+}
+
+// the knobs for random_dfa and random_nfa. ratios are probabilities between 0 and 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomSpec {
+    pub states: usize,
+    // symbols in the alphabet, at most 256. see random_symbols for which ones
+    pub alphabet_size: usize,
+    // chance that a cell has a transition at all. in an NFA, also the chance of each extra destination
+    pub density: f64,
+    // chance that a state accepts
+    pub accepting_ratio: f64,
+    // NFA only: chance that a state has lambda edges (the last column), and of each extra one
+    pub lambda_ratio: f64,
+}
+
+impl Default for RandomSpec {
+    fn default() -> Self {
+        RandomSpec { states: 8, alphabet_size: 2, density: 0.8, accepting_ratio: 0.3, lambda_ratio: 0.1 }
+    }
+}
+
+// the symbols of a generated alphabet in column order. letters and digits first so small alphabets stay
+// readable, then the rest of the bytes in order
+pub fn random_symbols(size: usize) -> Vec<u8> {
+    let mut order: Vec<u8> = (b'a'..=b'z').chain(b'0'..=b'9').chain(b'A'..=b'Z').collect();
+    order.extend((0..=255u8).filter(|b| !b.is_ascii_alphanumeric()));
+    order.truncate(size);
+    order
+}
+
+// random_symbols as an alphabet map, symbol i in column i
+pub fn random_alphabet(size: usize) -> HashMap<u8, usize> {
+    random_symbols(size).into_iter().enumerate().map(|(column, b)| (b, column)).collect()
+}
+
+// a destination set: one state with probability p, then each extra one with probability p again
+fn destinations(rng: &mut Rng, states: usize, p: f64) -> HashSet<u32> {
+    let mut set = HashSet::new();
+    for _ in 0..states {
+        if !rng.chance(p) {
+            break;
+        }
+        set.insert(rng.below(states) as u32);
+    }
+    set
+}
+
+// a DFA with spec.states states, started in state 0. nothing makes the states reachable or useful, so
+// low densities give plenty of dead and unreachable states for minimize to clean up
+pub fn random_dfa(spec: &RandomSpec, seed: u64) -> DFA {
+    let mut rng = Rng::new(seed);
+    let states = spec.states.max(1);
+    let alphabet_size = spec.alphabet_size.min(256);
+
+    let rows: Vec<StateRow> = (0..states)
+        .map(|id| StateRow {
+            accepting: rng.chance(spec.accepting_ratio),
+            state_id: id as u32,
+            transitions: (0..alphabet_size)
+                .map(|_| rng.chance(spec.density).then(|| rng.below(states) as u32))
+                .collect(),
+        })
+        .collect();
+    DFA::new(rows, random_alphabet(alphabet_size)).expect("generated rows are dense and in range")
+}
+
+// an NFA with spec.states states, started in state 0. it has one column per symbol plus a last one for lambda
+pub fn random_nfa(spec: &RandomSpec, seed: u64) -> NFA {
+    let mut rng = Rng::new(seed);
+    let states = spec.states.max(1);
+    let alphabet_size = spec.alphabet_size.min(256);
+
+    let rows: Vec<NfaStateRow> = (0..states)
+        .map(|id| {
+            let accepting = rng.chance(spec.accepting_ratio);
+            let mut transitions: Vec<HashSet<u32>> = (0..alphabet_size)
+                .map(|_| destinations(&mut rng, states, spec.density))
+                .collect();
+            transitions.push(destinations(&mut rng, states, spec.lambda_ratio));
+            NfaStateRow { accepting, state_id: id as u32, transitions }
+        })
+        .collect();
+    NFA::new(rows, random_alphabet(alphabet_size), 0).expect("generated table has states")
+}
@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::alphabetencoding;
+use crate::error::{Error, Result};
+use crate::nfa::{NfaStateRow, NFA};
+
+// regexes over a scanner alphabet, compiled to an NFA by Thompson construction
+//
+//   a b        concatenation          a|b       alternation
+//   a* a+ a?   zero or more, one or more, zero or one
+//   ( )        grouping, () alone matches the empty string
+//   [a-fz]     any one of the listed symbols, - makes a range
+//   .          any symbol of the alphabet
+//   x20        a symbol by its hex value, like the alphabet encoding. x78 is x itself, x2a is a literal *
+//
+// every other byte that the alphabet encoding keeps literal stands for itself, and so does a space, like decode
+// reads it. : and \ have to be written x3a and x5c here too. inside [ ] only ] and - are special. every symbol
+// has to be in the alphabet the regex is compiled against, except the ends of a range
+//
+// the parser follows the LL(1) grammar in regex.cfg, one function per nonterminal. `cfg src/regex.cfg` prints
+// its predict sets and checks that it is still LL(1) after a change
+
+// a parsed regex. groups leave no node of their own, they only decide the shape of the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexAst {
+    // only the empty string: an empty regex, branch or group
+    Empty,
+    Symbol(u8),
+    // . any symbol of the alphabet
+    Any,
+    // [ ] as inclusive ranges, a lone symbol is a range of one
+    Class(Vec<(u8, u8)>),
+    Sequence(Vec<RegexAst>),
+    Alternation(Vec<RegexAst>),
+    Star(Box<RegexAst>),
+    Plus(Box<RegexAst>),
+    Optional(Box<RegexAst>),
+}
+
+// the terminals of regex.cfg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Pipe,
+    Star,
+    Plus,
+    Question,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Dash,
+    Dot,
+    Symbol,
+    End,
+}
+
+impl Token {
+    fn describe(self) -> &'static str {
+        match self {
+            Token::Pipe => "|",
+            Token::Star => "*",
+            Token::Plus => "+",
+            Token::Question => "?",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBracket => "[",
+            Token::RBracket => "]",
+            Token::Dash => "-",
+            Token::Dot => ".",
+            Token::Symbol => "a symbol",
+            Token::End => "the end of the regex",
+        }
+    }
+}
+
+// a token with the 1 based column it starts at. byte is only meaningful for a symbol
+#[derive(Debug, Clone, Copy)]
+struct Lexeme {
+    token: Token,
+    column: usize,
+    len: usize,
+    byte: u8,
+}
+
+fn malformed(pattern: &str, column: usize, message: String, expected: Vec<String>) -> Error {
+    Error::MalformedRegex { pattern: pattern.to_string(), column, message, expected }
+}
+
+// split the pattern into terminals. inside [ ] everything but ] and - is a symbol, so [(*] needs no escapes
+fn lex(pattern: &str) -> Result<Vec<Lexeme>> {
+    let bytes = pattern.as_bytes();
+    let mut lexemes = Vec::new();
+    let mut in_class = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let column = i + 1;
+        let special = match (in_class, bytes[i]) {
+            (_, b']') => Some(Token::RBracket),
+            (true, b'-') => Some(Token::Dash),
+            (true, _) => None,
+            (false, b'|') => Some(Token::Pipe),
+            (false, b'*') => Some(Token::Star),
+            (false, b'+') => Some(Token::Plus),
+            (false, b'?') => Some(Token::Question),
+            (false, b'(') => Some(Token::LParen),
+            (false, b')') => Some(Token::RParen),
+            (false, b'[') => Some(Token::LBracket),
+            (false, b'.') => Some(Token::Dot),
+            (false, _) => None,
+        };
+        if let Some(token) = special {
+            in_class = match token {
+                Token::LBracket => true,
+                Token::RBracket => false,
+                _ => in_class,
+            };
+            lexemes.push(Lexeme { token, column, len: 1, byte: bytes[i] });
+            i += 1;
+            continue;
+        }
+
+        let (byte, len) = match bytes[i] {
+            b'x' => {
+                let hex = bytes.get(i + 1..i + 3).filter(|hex| hex.iter().all(|b| b.is_ascii_hexdigit()));
+                match hex {
+                    // two ascii hex digits are always valid utf-8
+                    Some(hex) => (u8::from_str_radix(std::str::from_utf8(hex).unwrap(), 16).unwrap(), 3),
+                    None => return Err(malformed(pattern, column, "x must be followed by two hex digits".to_string(), Vec::new())),
+                }
+            }
+            b if alphabetencoding::is_literal(b) || b == b' ' => (b, 1),
+            b => return Err(malformed(pattern, column, format!("write x{:02x} instead of the raw byte", b), Vec::new())),
+        };
+        lexemes.push(Lexeme { token: Token::Symbol, column, len, byte });
+        i += len;
+    }
+    lexemes.push(Lexeme { token: Token::End, column: bytes.len() + 1, len: 0, byte: 0 });
+    Ok(lexemes)
+}
+
+// FIRST(Quantified), the tokens that can start an atom
+const ATOM_START: [Token; 4] = [Token::LParen, Token::LBracket, Token::Dot, Token::Symbol];
+
+// FOLLOW(Sequence), where a sequence or an alternation branch can end
+const SEQUENCE_END: [Token; 3] = [Token::Pipe, Token::RParen, Token::End];
+
+const QUANTIFIERS: [Token; 3] = [Token::Star, Token::Plus, Token::Question];
+
+// recursive descent over the lexemes. each function picks a production of its nonterminal by the predict sets
+// of regex.cfg, so one token of lookahead is always enough
+struct Parser<'a> {
+    pattern: &'a str,
+    lexemes: Vec<Lexeme>,
+    position: usize,
+    // symbols outside of range ends are checked against this when there is one
+    alphabet: Option<&'a [u8]>,
+    // terminals that would also have been fine here, from nonterminals that chose lambda since the last token.
+    // they go into the error along with whatever the parser was trying to match
+    expected: Vec<Token>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Token {
+        self.lexemes[self.position].token
+    }
+
+    fn advance(&mut self) -> Lexeme {
+        let lexeme = self.lexemes[self.position];
+        self.position += 1;
+        self.expected.clear();
+        lexeme
+    }
+
+    // a nonterminal went to lambda, so its other productions could have started here too
+    fn skipped(&mut self, tokens: &[Token]) {
+        self.expected.extend_from_slice(tokens);
+    }
+
+    // the current token fits nothing. reports it along with every terminal that would have fit
+    fn unexpected(&self, tokens: &[Token]) -> Error {
+        let mut expected: Vec<String> = Vec::new();
+        for token in self.expected.iter().chain(tokens) {
+            if !expected.iter().any(|e| e == token.describe()) {
+                expected.push(token.describe().to_string());
+            }
+        }
+        let lexeme = self.lexemes[self.position];
+        let found = match lexeme.token {
+            Token::End => "the end of the regex".to_string(),
+            _ => format!("'{}'", &self.pattern[lexeme.column - 1..lexeme.column - 1 + lexeme.len]),
+        };
+        malformed(self.pattern, lexeme.column, format!("unexpected {}", found), expected)
+    }
+
+    fn expect(&mut self, token: Token) -> Result<Lexeme> {
+        if self.peek() == token {
+            Ok(self.advance())
+        } else {
+            Err(self.unexpected(&[token]))
+        }
+    }
+
+    fn check_alphabet(&self, lexeme: Lexeme) -> Result<u8> {
+        match self.alphabet {
+            Some(alphabet) if !alphabet.contains(&lexeme.byte) => {
+                Err(malformed(self.pattern, lexeme.column, format!("x{:02x} is not in the alphabet", lexeme.byte), Vec::new()))
+            }
+            _ => Ok(lexeme.byte),
+        }
+    }
+
+    // Regex -> Alternation $
+    fn regex(&mut self) -> Result<RegexAst> {
+        let regex = self.alternation()?;
+        self.expect(Token::End)?;
+        Ok(regex)
+    }
+
+    // Alternation -> Sequence AlternationTail
+    // AlternationTail -> pipe Sequence AlternationTail | lambda
+    fn alternation(&mut self) -> Result<RegexAst> {
+        let mut branches = vec![self.sequence()?];
+        loop {
+            match self.peek() {
+                Token::Pipe => {
+                    self.advance();
+                    branches.push(self.sequence()?);
+                }
+                Token::RParen | Token::End => {
+                    self.skipped(&[Token::Pipe]);
+                    break;
+                }
+                _ => return Err(self.unexpected(&SEQUENCE_END)),
+            }
+        }
+        Ok(if branches.len() == 1 { branches.pop().unwrap() } else { RegexAst::Alternation(branches) })
+    }
+
+    // Sequence -> Quantified Sequence | lambda
+    fn sequence(&mut self) -> Result<RegexAst> {
+        let mut parts = Vec::new();
+        loop {
+            match self.peek() {
+                token if ATOM_START.contains(&token) => parts.push(self.quantified()?),
+                token if SEQUENCE_END.contains(&token) => {
+                    self.skipped(&ATOM_START);
+                    break;
+                }
+                _ => return Err(self.unexpected(&[ATOM_START.as_slice(), &SEQUENCE_END].concat())),
+            }
+        }
+        Ok(match parts.len() {
+            0 => RegexAst::Empty,
+            1 => parts.pop().unwrap(),
+            _ => RegexAst::Sequence(parts),
+        })
+    }
+
+    // Quantified -> Atom Quantifiers
+    // Quantifiers -> star Quantifiers | plus Quantifiers | question Quantifiers | lambda
+    fn quantified(&mut self) -> Result<RegexAst> {
+        let mut regex = self.atom()?;
+        loop {
+            regex = match self.peek() {
+                Token::Star => RegexAst::Star(Box::new(regex)),
+                Token::Plus => RegexAst::Plus(Box::new(regex)),
+                Token::Question => RegexAst::Optional(Box::new(regex)),
+                // FOLLOW(Quantifiers) is everything that can come after an atom
+                token if ATOM_START.contains(&token) || SEQUENCE_END.contains(&token) => {
+                    self.skipped(&QUANTIFIERS);
+                    return Ok(regex);
+                }
+                _ => return Err(self.unexpected(&[QUANTIFIERS.as_slice(), &ATOM_START, &SEQUENCE_END].concat())),
+            };
+            self.advance();
+        }
+    }
+
+    // Atom -> lparen Alternation rparen | lbracket ClassItem ClassItems rbracket | dot | symbol
+    // ClassItems -> ClassItem ClassItems | lambda
+    fn atom(&mut self) -> Result<RegexAst> {
+        match self.peek() {
+            Token::LParen => {
+                self.advance();
+                let inner = self.alternation()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Token::LBracket => {
+                self.advance();
+                let mut ranges = vec![self.class_item()?];
+                loop {
+                    match self.peek() {
+                        Token::Symbol => ranges.push(self.class_item()?),
+                        Token::RBracket => {
+                            self.skipped(&[Token::Symbol]);
+                            break;
+                        }
+                        _ => return Err(self.unexpected(&[Token::Symbol, Token::RBracket])),
+                    }
+                }
+                self.expect(Token::RBracket)?;
+                Ok(RegexAst::Class(ranges))
+            }
+            Token::Dot => {
+                self.advance();
+                Ok(RegexAst::Any)
+            }
+            Token::Symbol => {
+                let lexeme = self.advance();
+                Ok(RegexAst::Symbol(self.check_alphabet(lexeme)?))
+            }
+            _ => Err(self.unexpected(&ATOM_START)),
+        }
+    }
+
+    // ClassItem -> symbol Range
+    // Range -> dash symbol | lambda
+    fn class_item(&mut self) -> Result<(u8, u8)> {
+        let first = self.expect(Token::Symbol)?;
+        match self.peek() {
+            Token::Dash => {
+                self.advance();
+                let last = self.expect(Token::Symbol)?;
+                if last.byte < first.byte {
+                    let message = format!("range {}-{} runs backwards", first.byte as char, last.byte as char);
+                    return Err(malformed(self.pattern, last.column, message, Vec::new()));
+                }
+                // a range can run over bytes the scanner doesn't read, those are just left out
+                Ok((first.byte, last.byte))
+            }
+            Token::Symbol | Token::RBracket => {
+                self.skipped(&[Token::Dash]);
+                let byte = self.check_alphabet(first)?;
+                Ok((byte, byte))
+            }
+            _ => Err(self.unexpected(&[Token::Dash, Token::Symbol, Token::RBracket])),
+        }
+    }
+}
+
+fn parse(pattern: &str, alphabet: Option<&[u8]>) -> Result<RegexAst> {
+    let mut parser = Parser { pattern, lexemes: lex(pattern)?, position: 0, alphabet, expected: Vec::new() };
+    parser.regex()
+}
+
+// parse a regex on its own, without an alphabet to check its symbols against
+pub fn parse_regex(pattern: &str) -> Result<RegexAst> {
+    parse(pattern, None)
+}
+
+// the NFA being built. the lambda column is the last one
+struct Thompson<'a> {
+    rows: Vec<NfaStateRow>,
+    alphabet: &'a [u8],
+    columns: HashMap<u8, usize>,
+    lambda: usize,
+}
+
+// a piece of the machine with one way in and one way out
+struct Fragment {
+    start: u32,
+    accept: u32,
+}
+
+impl Thompson<'_> {
+    fn state(&mut self) -> u32 {
+        let id = self.rows.len() as u32;
+        self.rows.push(NfaStateRow { accepting: false, state_id: id, transitions: vec![HashSet::new(); self.lambda + 1] });
+        id
+    }
+
+    fn edge(&mut self, from: u32, column: usize, to: u32) {
+        self.rows[from as usize].transitions[column].insert(to);
+    }
+
+    // two states joined by an edge for each of the symbols that the alphabet has
+    fn symbols(&mut self, symbols: impl Iterator<Item = u8>) -> Fragment {
+        let (start, accept) = (self.state(), self.state());
+        for symbol in symbols {
+            if let Some(&column) = self.columns.get(&symbol) {
+                self.edge(start, column, accept);
+            }
+        }
+        Fragment { start, accept }
+    }
+
+    fn build(&mut self, regex: &RegexAst) -> Fragment {
+        match regex {
+            RegexAst::Empty => {
+                let (start, accept) = (self.state(), self.state());
+                self.edge(start, self.lambda, accept);
+                Fragment { start, accept }
+            }
+            RegexAst::Symbol(symbol) => self.symbols(std::iter::once(*symbol)),
+            RegexAst::Any => self.symbols(self.alphabet.iter().copied()),
+            RegexAst::Class(ranges) => self.symbols(ranges.iter().flat_map(|&(first, last)| first..=last)),
+            RegexAst::Sequence(parts) => {
+                let fragments: Vec<Fragment> = parts.iter().map(|part| self.build(part)).collect();
+                for pair in fragments.windows(2) {
+                    self.edge(pair[0].accept, self.lambda, pair[1].start);
+                }
+                Fragment { start: fragments[0].start, accept: fragments[fragments.len() - 1].accept }
+            }
+            RegexAst::Alternation(branches) => {
+                let start = self.state();
+                let fragments: Vec<Fragment> = branches.iter().map(|branch| self.build(branch)).collect();
+                let accept = self.state();
+                for fragment in fragments {
+                    self.edge(start, self.lambda, fragment.start);
+                    self.edge(fragment.accept, self.lambda, accept);
+                }
+                Fragment { start, accept }
+            }
+            RegexAst::Star(inner) | RegexAst::Plus(inner) | RegexAst::Optional(inner) => {
+                let start = self.state();
+                let fragment = self.build(inner);
+                let accept = self.state();
+                self.edge(start, self.lambda, fragment.start);
+                self.edge(fragment.accept, self.lambda, accept);
+                // star and optional can skip the inside, star and plus can go around again
+                if !matches!(regex, RegexAst::Plus(_)) {
+                    self.edge(start, self.lambda, accept);
+                }
+                if !matches!(regex, RegexAst::Optional(_)) {
+                    self.edge(fragment.accept, self.lambda, fragment.start);
+                }
+                Fragment { start, accept }
+            }
+        }
+    }
+}
+
+// Thompson construction of pattern over alphabet, whose symbols become the columns in the order given. a repeated
+// symbol keeps its first column. the NFA starts in state 0 and has exactly one accepting state
+pub fn regex_to_nfa(pattern: &str, alphabet: &[u8]) -> Result<NFA> {
+    if alphabet.is_empty() {
+        return Err(malformed(pattern, 1, "the alphabet is empty".to_string(), Vec::new()));
+    }
+    let regex = parse(pattern, Some(alphabet))?;
+
+    // a second column for the same symbol would be one that nothing reads, and lambda has to come after the last one
+    let mut symbols: Vec<u8> = Vec::with_capacity(alphabet.len());
+    for &b in alphabet {
+        if !symbols.contains(&b) {
+            symbols.push(b);
+        }
+    }
+    let columns: HashMap<u8, usize> = symbols.iter().enumerate().map(|(column, &b)| (b, column)).collect();
+    let mut thompson = Thompson { rows: Vec::new(), alphabet: &symbols, lambda: columns.len(), columns };
+    // state 0 only leads into the machine, so the start state is 0 like every NFA file expects
+    let start = thompson.state();
+    let fragment = thompson.build(&regex);
+    thompson.edge(start, thompson.lambda, fragment.start);
+    thompson.rows[fragment.accept as usize].accepting = true;
+
+    NFA::new(thompson.rows, thompson.columns, start)
+}
@@ -0,0 +1,217 @@
+use std::io::{ErrorKind, Read};
+
+use crate::dfa::DFA;
+use crate::error::{Error, Result};
+
+// how much gets read from the underlying reader at once
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// one dfa run over input that arrives in pieces. the state survives between calls to feed, so a token can
+// start in one chunk and end in the next. every offset is counted from the start of the stream
+#[derive(Debug, Clone)]
+pub struct StreamMatcher<'a> {
+    dfa: &'a DFA,
+    // None once the dfa hit an E or a byte outside its alphabet. nothing after that can match
+    state: Option<usize>,
+    start: u64,
+    position: u64,
+    longest: Option<u64>,
+}
+
+impl<'a> StreamMatcher<'a> {
+    // a matcher whose first byte is at offset start of the stream
+    pub fn new(dfa: &'a DFA, start: u64) -> Self {
+        let mut matcher = StreamMatcher { dfa, state: None, start, position: start, longest: None };
+        matcher.restart(start);
+        matcher
+    }
+
+    // go back to the start state for a match beginning at offset start
+    pub fn restart(&mut self, start: u64) {
+        self.state = Some(self.dfa.start_state);
+        self.start = start;
+        self.position = start;
+        self.longest = self.dfa.states[self.dfa.start_state].accepting.then_some(start);
+    }
+
+    // run the next bytes of the stream. returns how many were read before the dfa died, all of them if it didn't
+    pub fn feed(&mut self, chunk: &[u8]) -> usize {
+        for (i, symbol) in chunk.iter().enumerate() {
+            let next = self.state
+                .and_then(|state| self.dfa.alphabet.get(symbol).and_then(|&column| self.dfa.states[state].transitions[column]));
+            match next {
+                Some(next) => {
+                    self.state = Some(next as usize);
+                    self.position += 1;
+                    if self.dfa.states[next as usize].accepting {
+                        self.longest = Some(self.position);
+                    }
+                }
+                None => {
+                    self.state = None;
+                    return i;
+                }
+            }
+        }
+        chunk.len()
+    }
+
+    // true once no amount of further input can make the match longer
+    pub fn is_dead(&self) -> bool {
+        self.state.is_none()
+    }
+
+    // stream offset where this match started
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    // stream offset of the next byte the matcher expects
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    // stream offset just past the longest accepted prefix so far, None if nothing was accepted
+    pub fn longest_match(&self) -> Option<u64> {
+        self.longest
+    }
+}
+
+// a token found by StreamTokenizer. start and end are stream offsets, line and column where it begins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamToken {
+    pub recognizer: usize,
+    pub start: u64,
+    pub end: u64,
+    pub line: usize,
+    pub column: usize,
+    pub bytes: Vec<u8>,
+}
+
+// maximal munch over a reader with a list of recognizers, ties going to the earlier one (LUTHOR's rule)
+// only the bytes since the current token started are kept, so memory is bounded by the longest lookahead
+// rather than by the size of the input
+pub struct StreamTokenizer<'a, R: Read> {
+    reader: R,
+    matchers: Vec<StreamMatcher<'a>>,
+    // bytes from the start of the current token that were already read from the reader
+    buffer: Vec<u8>,
+    buffer_start: u64,
+    chunk: Vec<u8>,
+    line: usize,
+    column: usize,
+    eof: bool,
+    failed: bool,
+}
+
+impl<'a, R: Read> StreamTokenizer<'a, R> {
+    pub fn new(reader: R, recognizers: &[&'a DFA]) -> Self {
+        Self::with_chunk_size(reader, recognizers, CHUNK_SIZE)
+    }
+
+    // same as new, reading at most chunk_size bytes at a time
+    pub fn with_chunk_size(reader: R, recognizers: &[&'a DFA], chunk_size: usize) -> Self {
+        StreamTokenizer {
+            reader,
+            matchers: recognizers.iter().map(|dfa| StreamMatcher::new(dfa, 0)).collect(),
+            buffer: Vec::new(),
+            buffer_start: 0,
+            chunk: vec![0; chunk_size.max(1)],
+            line: 1,
+            column: 1,
+            eof: false,
+            failed: false,
+        }
+    }
+
+    // read one more chunk and run every live matcher over it
+    fn read_chunk(&mut self) -> Result<()> {
+        let read = loop {
+            match self.reader.read(&mut self.chunk) {
+                Ok(read) => break read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(Error::Io(e)),
+            }
+        };
+        if read == 0 {
+            self.eof = true;
+            return Ok(());
+        }
+
+        let chunk = &self.chunk[..read];
+        for matcher in self.matchers.iter_mut().filter(|m| !m.is_dead()) {
+            matcher.feed(chunk);
+        }
+        self.buffer.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    // the recognizer with the longest nonempty match and its length. an empty match never counts as a token
+    fn best_match(&self) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        for (i, matcher) in self.matchers.iter().enumerate() {
+            let length = matcher.longest_match().map_or(0, |end| (end - self.buffer_start) as usize);
+            if length > best.map_or(0, |b| b.1) {
+                best = Some((i, length));
+            }
+        }
+        best
+    }
+
+    fn next_token(&mut self) -> Result<Option<StreamToken>> {
+        // keep reading while some recognizer could still find a longer match
+        while !self.eof && self.matchers.iter().any(|m| !m.is_dead()) {
+            self.read_chunk()?;
+        }
+        if self.eof && self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let (recognizer, length) = match self.best_match() {
+            Some(best) => best,
+            None => return Err(Error::NoMatch { line: self.line, column: self.column }),
+        };
+
+        let bytes: Vec<u8> = self.buffer.drain(..length).collect();
+        let token = StreamToken {
+            recognizer,
+            start: self.buffer_start,
+            end: self.buffer_start + length as u64,
+            line: self.line,
+            column: self.column,
+            bytes,
+        };
+        for &c in &token.bytes {
+            if c == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+
+        // the lookahead past this token is the start of the next one
+        self.buffer_start = token.end;
+        for matcher in &mut self.matchers {
+            matcher.restart(token.end);
+            matcher.feed(&self.buffer);
+        }
+        Ok(Some(token))
+    }
+}
+
+impl<R: Read> Iterator for StreamTokenizer<'_, R> {
+    type Item = Result<StreamToken>;
+
+    // stops for good after the first error
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_token();
+        if result.is_err() {
+            self.failed = true;
+        }
+        result.transpose()
+    }
+}
@@ -0,0 +1,80 @@
+use crate::alphabetencoding;
+use crate::error::{Error, Result};
+
+// one `termid value line col` row of a token file like LUTHOR writes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenLine {
+    pub termid: String,
+    pub value: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+// put an encoded token value in its one canonical form: lowercase hex and only the escapes that are required
+// so x61 and a, or x5C and x5c, come out the same
+pub fn normalize_value(encoded_token: &str) -> Result<String> {
+    Ok(alphabetencoding::encode(&alphabetencoding::decode(encoded_token)?))
+}
+
+// parse a token file, normalizing every value. blank lines are skipped like normoutput does, but every other
+// line needs exactly 4 fields and numbers for line and col. the old shell loop folded extra fields into the last
+// one and left missing ones empty, here one bad line fails the whole file with its line number
+pub fn parse_token_file(file: &str, contents: &str) -> Result<Vec<TokenLine>> {
+    let mut tokens = Vec::new();
+
+    for (line_index, line) in contents.lines().enumerate() {
+        let line_number = line_index + 1;
+        let malformed = |message: String| Error::MalformedRow { file: file.to_string(), line: line_number, message };
+
+        let delimited_line: Vec<&str> = line.split_whitespace().collect();
+        if delimited_line.is_empty() {
+            continue;
+        }
+        if delimited_line.len() != 4 {
+            return Err(malformed(format!("read {} but expected <termid> <value> <line> <col>", line)));
+        }
+
+        let value = normalize_value(delimited_line[1]).map_err(|e| malformed(e.to_string()))?;
+        let line = delimited_line[2].parse::<usize>()
+            .map_err(|_| malformed(format!("expected a line number, read {}", delimited_line[2])))?;
+        let column = delimited_line[3].parse::<usize>()
+            .map_err(|_| malformed(format!("expected a column number, read {}", delimited_line[3])))?;
+
+        tokens.push(TokenLine { termid: delimited_line[0].to_string(), value, line, column });
+    }
+    Ok(tokens)
+}
+
+// one spot where two token files disagree. index counts tokens from 1, None means that file ran out first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDifference {
+    pub index: usize,
+    pub left: Option<TokenLine>,
+    pub right: Option<TokenLine>,
+}
+
+// the whole token file in canonical form, one token per line
+pub fn normalize(file: &str, contents: &str) -> Result<String> {
+    let mut output = String::new();
+    for token in parse_token_file(file, contents)? {
+        output.push_str(&format!("{} {} {} {}\n", token.termid, token.value, token.line, token.column));
+    }
+    Ok(output)
+}
+
+// compare two token files the way grader.sh does (normalize both, then line by line)
+// gives back every position where they differ, so an empty vec means they match
+pub fn compare(left_file: &str, left: &str, right_file: &str, right: &str) -> Result<Vec<TokenDifference>> {
+    let left_tokens = parse_token_file(left_file, left)?;
+    let right_tokens = parse_token_file(right_file, right)?;
+
+    let mut differences = Vec::new();
+    for i in 0..left_tokens.len().max(right_tokens.len()) {
+        let l = left_tokens.get(i);
+        let r = right_tokens.get(i);
+        if l != r {
+            differences.push(TokenDifference { index: i + 1, left: l.cloned(), right: r.cloned() });
+        }
+    }
+    Ok(differences)
+}
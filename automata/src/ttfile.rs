@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use crate::dfa::{StateRow, DFA};
+use crate::error::{Diagnostic, Error, Result};
+use crate::nfa::{NfaStateRow, NFA};
+
+// one row of a table before it becomes a StateRow or an NfaStateRow. every cell is the set of destinations
+struct Row {
+    accepting: bool,
+    state_id: u32,
+    cells: Vec<Vec<u32>>,
+}
+
+// a destination and where it was written, checked once we know how many rows there are
+struct Target {
+    line: usize,
+    column: usize,
+    token: String,
+    state: u32,
+}
+
+// the whitespace separated fields of a line with the 1 based column each one starts at
+pub(crate) fn fields(line: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, c) in line.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                fields.push((s + 1, &line[s..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        fields.push((s + 1, &line[s..]));
+    }
+    fields
+}
+
+// the shared part of both formats: `+/- id cell cell ...`, blank lines skipped, every state id used once,
+// every row as wide as expected and every destination an existing state. a DFA cell is E or one state,
+// an NFA cell is E or a comma separated list of states. every problem in the file is collected, not just the first
+// DFAs renumber their states when they are built, so only NFA tables (dense) need the ids to equal the row index
+fn parse_rows(file: &str, contents: &str, columns: Option<usize>, sets: bool, dense: bool) -> Result<Vec<Row>> {
+    let mut rows: Vec<Row> = Vec::new();
+    // the line each state id was defined on
+    let mut defined: HashMap<u32, usize> = HashMap::new();
+    let mut targets: Vec<Target> = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut expected_columns = columns;
+
+    for (line_index, line) in contents.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut report = |column: usize, token: &str, message: String| {
+            diagnostics.push(Diagnostic { line: line_number, column, token: token.to_string(), message });
+        };
+
+        let fields = fields(line);
+        if fields.is_empty() {
+            continue;
+        }
+        if fields.len() < 2 {
+            report(fields[0].0, fields[0].1, "expected +/- followed by a state id".to_string());
+            continue;
+        }
+
+        let (sign_column, sign) = fields[0];
+        if sign != "+" && sign != "-" {
+            report(sign_column, sign, "first symbol must be + or -".to_string());
+        }
+
+        let (id_column, id) = fields[1];
+        let row_index = rows.len() as u32;
+        let state_id = match id.parse::<u32>() {
+            Ok(state_id) => {
+                if dense && state_id != row_index {
+                    report(id_column, id, format!("state id must match its row, expected {}", row_index));
+                } else if let Some(first) = defined.get(&state_id) {
+                    report(id_column, id, format!("state {} was already defined on line {}", state_id, first));
+                }
+                defined.entry(state_id).or_insert(line_number);
+                state_id
+            }
+            Err(_) => {
+                report(id_column, id, "expected a nonnegative integer state id".to_string());
+                row_index
+            }
+        };
+
+        // the first row sets the width when there is no alphabet to check against
+        let cells = &fields[2..];
+        let width = *expected_columns.get_or_insert(cells.len());
+        if cells.len() > width {
+            let (column, token) = cells[width];
+            report(column, token, format!("expected {} transitions, got {}", width, cells.len()));
+        } else if cells.len() < width {
+            report(line.trim_end().len() + 1, "", format!("expected {} transitions, got {}", width, cells.len()));
+        }
+
+        let mut row = Row { accepting: sign == "+", state_id, cells: Vec::with_capacity(cells.len()) };
+        for &(column, cell) in cells {
+            let mut destinations = Vec::new();
+            if cell != "E" {
+                let parts: Vec<&str> = if sets { cell.split(',').collect() } else { vec![cell] };
+                for part in parts {
+                    match part.parse::<u32>() {
+                        Ok(state) => {
+                            targets.push(Target { line: line_number, column, token: cell.to_string(), state });
+                            destinations.push(state);
+                        }
+                        Err(_) if sets => report(column, cell, "expected E or a comma separated list of state ids".to_string()),
+                        Err(_) => report(column, cell, "expected E or a state id".to_string()),
+                    }
+                }
+            }
+            row.cells.push(destinations);
+        }
+        rows.push(row);
+    }
+
+    for target in targets {
+        if !defined.contains_key(&target.state) {
+            diagnostics.push(Diagnostic {
+                line: target.line,
+                column: target.column,
+                token: target.token,
+                message: format!("state {} does not exist, the table has {} states", target.state, rows.len()),
+            });
+        }
+    }
+
+    if rows.is_empty() && diagnostics.is_empty() {
+        diagnostics.push(Diagnostic { line: 1, column: 1, token: String::new(), message: "table has no states".to_string() });
+    }
+
+    if !diagnostics.is_empty() {
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        return Err(Error::MalformedTable { file: file.to_string(), diagnostics });
+    }
+    Ok(rows)
+}
+
+// a DFA transition table. columns is the number of transitions each row must have, None to take it from the first row
+pub fn parse_dfa_table(file: &str, contents: &str, columns: Option<usize>) -> Result<Vec<StateRow>> {
+    let rows = parse_rows(file, contents, columns, false, false)?;
+    Ok(rows.into_iter()
+        .map(|row| StateRow {
+            accepting: row.accepting,
+            state_id: row.state_id,
+            transitions: row.cells.into_iter().map(|cell| cell.first().copied()).collect(),
+        })
+        .collect())
+}
+
+// an NFA transition table, where a cell can hold several destinations like 1,4,7
+pub fn parse_nfa_table(file: &str, contents: &str, columns: Option<usize>) -> Result<Vec<NfaStateRow>> {
+    let rows = parse_rows(file, contents, columns, true, true)?;
+    Ok(rows.into_iter()
+        .map(|row| NfaStateRow {
+            accepting: row.accepting,
+            state_id: row.state_id,
+            transitions: row.cells.into_iter().map(|cell| cell.into_iter().collect::<HashSet<u32>>()).collect(),
+        })
+        .collect())
+}
+
+fn read(path: &str) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| Error::MissingFile { path: path.to_string(), source: e })
+}
+
+// the width a table over this alphabet must have. an empty alphabet (DFAMINIMIZE) leaves it to the first row
+fn alphabet_columns(alphabet: &HashMap<u8, usize>) -> Option<usize> {
+    (!alphabet.is_empty()).then_some(alphabet.len())
+}
+
+// load and validate a DFA from a .tt file. state ids can be in any order and have gaps, they are renumbered
+// to row indexes (DFA::original_ids keeps them). start is an id from the file, None for DFA::new's default
+pub fn load_dfa(path: &str, alphabet: HashMap<u8, usize>, start: Option<u32>) -> Result<DFA> {
+    let states = parse_dfa_table(path, &read(path)?, alphabet_columns(&alphabet))?;
+    match start {
+        Some(start) => DFA::with_start_state(states, alphabet, start),
+        None => DFA::new(states, alphabet),
+    }
+}
+
+// load and validate an NFA from a .tt file. over an alphabet, the table has a column per symbol and then the
+// lambda column last
+pub fn load_nfa(path: &str, alphabet: HashMap<u8, usize>, start_state: u32) -> Result<NFA> {
+    let columns = alphabet_columns(&alphabet).map(|symbols| symbols + 1);
+    let states = parse_nfa_table(path, &read(path)?, columns)?;
+    NFA::new(states, alphabet, start_state)
+}
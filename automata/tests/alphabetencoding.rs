@@ -0,0 +1,63 @@
+use automata::alphabetencoding::{decode, encode};
+use automata::Error;
+
+#[test]
+fn every_single_byte_round_trips() {
+    for b in 0..=255u8 {
+        let encoded = encode(&[b]);
+        assert_eq!(decode(&encoded).unwrap(), vec![b], "byte 0x{:02x} encoded as {}", b, encoded);
+    }
+}
+
+#[test]
+fn every_byte_pair_round_trips() {
+    for hi in 0..=255u8 {
+        for lo in 0..=255u8 {
+            let token = [hi, lo];
+            assert_eq!(decode(&encode(&token)).unwrap(), token);
+        }
+    }
+}
+
+#[test]
+fn whole_byte_range_round_trips_as_one_token() {
+    let all: Vec<u8> = (0..=255u8).collect();
+    assert_eq!(decode(&encode(&all)).unwrap(), all);
+
+    let reversed: Vec<u8> = all.iter().rev().cloned().collect();
+    assert_eq!(decode(&encode(&reversed)).unwrap(), reversed);
+}
+
+#[test]
+fn encoded_tokens_only_use_safe_characters() {
+    let all: Vec<u8> = (0..=255u8).collect();
+    let encoded = encode(&all);
+    assert!(encoded.bytes().all(|c| c.is_ascii_graphic() && c != b':' && c != b'\\'));
+}
+
+#[test]
+fn non_ascii_bytes_stay_single_bytes() {
+    // used to come back as two byte utf-8 sequences
+    assert_eq!(encode(&[0x80, 0xff]), "x80xff");
+    assert_eq!(decode("x80xff").unwrap(), vec![0x80, 0xff]);
+}
+
+#[test]
+fn unsafe_characters_are_escaped() {
+    assert_eq!(encode(b"a:b\\x y\n"), "ax3abx5cx78x20yx0a");
+}
+
+#[test]
+fn decode_accepts_uppercase_hex_and_literal_space() {
+    assert_eq!(decode("x5Cooo x5c").unwrap(), b"\\ooo \\".to_vec());
+}
+
+#[test]
+fn decode_rejects_bad_escapes() {
+    for (token, offset) in [("x", 0), ("ax4", 1), ("x4g", 0), ("x+f", 0), ("ab:", 2), ("a\\", 1), ("x\u{e9}1", 0)] {
+        match decode(token) {
+            Err(Error::BadEscape { offset: found, .. }) => assert_eq!(found, offset, "token {}", token),
+            other => panic!("expected a bad escape for {}, got {:?}", token, other),
+        }
+    }
+}
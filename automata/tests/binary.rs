@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+
+use automata::binary::{self, CompiledRecognizer};
+use automata::dfa::DFA;
+use automata::nfa::{NfaStateRow, NFA};
+use automata::Error;
+
+mod common;
+use common::dfa_from_rows_with_start;
+
+// (ab)*, starting from row 1 so the start state has to survive the trip
+fn ab_star() -> DFA {
+    dfa_from_rows_with_start(&[(false, vec![None, Some(1)]), (true, vec![Some(0), None])], b"ab", 1)
+}
+
+// a keyword over a wide alphabet, mostly E, so it gets written sparse
+fn keyword() -> DFA {
+    let alphabet: Vec<u8> = (b'a'..=b'z').collect();
+    let mut rows = vec![(false, vec![None; 26]), (false, vec![None; 26]), (true, vec![None; 26])];
+    rows[0].1[(b'i' - b'a') as usize] = Some(1);
+    rows[1].1[(b'f' - b'a') as usize] = Some(2);
+    dfa_from_rows_with_start(&rows, &alphabet, 0)
+}
+
+fn saved(dfa: &DFA) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    dfa.save(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn dfas_round_trip() {
+    for dfa in [ab_star(), keyword()] {
+        let loaded = DFA::load(&mut &saved(&dfa)[..]).unwrap();
+        assert_eq!(loaded.print(), dfa.print());
+        assert_eq!(loaded.start_state, dfa.start_state);
+        assert_eq!(loaded.alphabet, dfa.alphabet);
+        assert_eq!(loaded.equivalent(&dfa), Ok(()));
+    }
+    // sparse is a lot smaller than 3 states * 26 columns * 4 bytes
+    assert!(saved(&keyword()).len() < 3 * 26 * 4);
+}
+
+#[test]
+fn nfas_round_trip() {
+    let states = vec![
+        NfaStateRow { accepting: false, state_id: 0, transitions: vec![HashSet::from([0, 1]), HashSet::new()] },
+        NfaStateRow { accepting: true, state_id: 1, transitions: vec![HashSet::new(), HashSet::from([0, 1])] },
+    ];
+    let nfa = NFA::new(states, HashMap::from([(b'a', 0), (b'b', 1)]), 0).unwrap();
+    let mut bytes = Vec::new();
+    nfa.save(&mut bytes).unwrap();
+
+    let loaded = NFA::load(&mut &bytes[..]).unwrap();
+    assert_eq!(loaded.print(), nfa.print());
+    assert_eq!(loaded.alphabet, nfa.alphabet);
+    assert_eq!(loaded.start_state_id, 0);
+}
+
+#[test]
+fn scanners_round_trip() {
+    let recognizers = vec![
+        CompiledRecognizer { dfa: keyword(), token_id: "if".to_string(), token_value: Some("x69x66".to_string()) },
+        CompiledRecognizer { dfa: ab_star(), token_id: "abs".to_string(), token_value: None },
+    ];
+    let mut bytes = Vec::new();
+    binary::save_scanner(&recognizers, &mut bytes).unwrap();
+    assert!(binary::is_compiled(&bytes));
+
+    let loaded = binary::load_scanner(&mut &bytes[..]).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!((loaded[0].token_id.as_str(), loaded[0].token_value.as_deref()), ("if", Some("x69x66")));
+    assert_eq!((loaded[1].token_id.as_str(), loaded[1].token_value.as_deref()), ("abs", None));
+    assert_eq!(loaded[1].dfa.equivalent(&ab_star()), Ok(()));
+}
+
+#[test]
+fn truncated_files_are_rejected() {
+    let bytes = saved(&keyword());
+    for len in 0..bytes.len() {
+        match DFA::load(&mut &bytes[..len]) {
+            Err(Error::MalformedBinary { .. }) => {}
+            other => panic!("{} of {} bytes loaded as {:?}", len, bytes.len(), other.map(|d| d.print())),
+        }
+    }
+}
+
+#[test]
+fn wrong_version_and_kind_are_rejected() {
+    let mut bytes = saved(&ab_star());
+    bytes[4] = 9;
+    match DFA::load(&mut &bytes[..]) {
+        Err(Error::UnsupportedVersion { found: 9, supported: binary::VERSION }) => {}
+        other => panic!("expected a version error, got {:?}", other.map(|d| d.print())),
+    }
+
+    // a DFA file isn't an NFA file
+    let bytes = saved(&ab_star());
+    assert!(matches!(NFA::load(&mut &bytes[..]), Err(Error::MalformedBinary { .. })));
+    assert!(matches!(DFA::load(&mut &b"not a dfa"[..]), Err(Error::MalformedBinary { .. })));
+}
@@ -0,0 +1,29 @@
+use automata::cli::split_args;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn flags_can_go_anywhere() {
+    let mut dfa = None;
+    let positional = split_args(&args("in.nfa --dfa=out.tt strings"), |arg| {
+        match arg.strip_prefix("--dfa=") {
+            Some(path) => dfa = Some(path.to_string()),
+            None => return Ok(false),
+        }
+        Ok(true)
+    }).unwrap();
+    assert_eq!(positional, ["in.nfa", "strings"]);
+    assert_eq!(dfa.as_deref(), Some("out.tt"));
+}
+
+#[test]
+fn unknown_flags_and_bad_values_are_errors() {
+    assert_eq!(split_args(&args("a --nope b"), |_| Ok(false)), Err("unknown flag --nope".to_string()));
+    let bad_count = |arg: &str| match arg.strip_prefix("--count=") {
+        Some(count) => count.parse::<usize>().map(|_| true).map_err(|_| format!("count needs a number, got {}", count)),
+        None => Ok(false),
+    };
+    assert_eq!(split_args(&args("--count=x"), bad_count), Err("count needs a number, got x".to_string()));
+}
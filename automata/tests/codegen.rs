@@ -0,0 +1,59 @@
+
+use automata::codegen::RustStyle;
+use automata::dfa::DFA;
+
+mod common;
+use common::dfa_from_rows;
+
+// the checked in files are exactly what to_rust writes for number(), and get compiled into this test
+mod generated_match {
+    include!("codegen/number_match.rs");
+}
+
+mod generated_table {
+    include!("codegen/number_table.rs");
+}
+
+// digits with an optional fraction: [0-9]+(\.[0-9]+)?
+fn number() -> DFA {
+    let alphabet = b"0123456789.";
+    let digits = |to: u32| vec![Some(to); 10];
+    let row = |digit_to: Option<u32>, dot_to: Option<u32>| {
+        let mut row = digit_to.map_or(vec![None; 10], digits);
+        row.push(dot_to);
+        row
+    };
+    dfa_from_rows(&[
+        (false, row(Some(1), None)),
+        (true, row(Some(1), Some(2))),
+        (false, row(Some(3), None)),
+        (true, row(Some(3), None)),
+    ], alphabet)
+}
+
+#[test]
+fn generated_source_is_up_to_date() {
+    assert_eq!(number().to_rust(RustStyle::Match), include_str!("codegen/number_match.rs"));
+    assert_eq!(number().to_rust(RustStyle::Table), include_str!("codegen/number_table.rs"));
+}
+
+#[test]
+fn generated_code_agrees_with_longest_match() {
+    let dfa = number();
+    for input in [&b""[..], b"7", b"42", b"3.", b"3.14", b"3.14.15", b".5", b"12a", b"0.0x", b"\xff"] {
+        let expected = dfa.longest_match(input);
+        assert_eq!(generated_match::matches(input), expected, "{:?}", input);
+        assert_eq!(generated_table::matches(input), expected, "{:?}", input);
+    }
+}
+
+#[test]
+fn degenerate_tables_return_a_constant() {
+    let accepts_empty = dfa_from_rows(&[(true, vec![None, None])], b"ab");
+    let code = accepts_empty.to_rust(RustStyle::Match);
+    assert!(code.contains("    Some(0)\n"));
+    assert!(!code.contains("for "));
+
+    let never_accepts = dfa_from_rows(&[(false, vec![Some(0), Some(0)])], b"ab");
+    assert!(never_accepts.to_rust(RustStyle::Match).contains("    None\n"));
+}
@@ -0,0 +1,20 @@
+// generated from a 4 state DFA over 11 symbols. do not edit, regenerate it from the transition table
+
+pub fn matches(input: &[u8]) -> Option<usize> {
+    let mut state: u32 = 0;
+    let mut longest = None;
+    for (i, &byte) in input.iter().enumerate() {
+        state = match (state, byte) {
+            (0, b'0'..=b'9') => 1,
+            (1, b'0'..=b'9') => 1,
+            (1, 0x2e) => 2,
+            (2, b'0'..=b'9') => 3,
+            (3, b'0'..=b'9') => 3,
+            _ => break,
+        };
+        if matches!(state, 1 | 3) {
+            longest = Some(i + 1);
+        }
+    }
+    longest
+}
@@ -0,0 +1,33 @@
+// fixtures shared by the test files. each one is its own crate and only uses some of these
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use automata::dfa::{StateRow, DFA};
+
+// rows numbered 0..n in the order given, starting in row 0. an empty alphabet leaves the map empty, like DFAMINIMIZE
+pub fn dfa_from_rows(rows: &[(bool, Vec<Option<u32>>)], alphabet: &[u8]) -> DFA {
+    dfa_from_rows_with_start(rows, alphabet, 0)
+}
+
+pub fn dfa_from_rows_with_start(rows: &[(bool, Vec<Option<u32>>)], alphabet: &[u8], start: u32) -> DFA {
+    let states = rows.iter().enumerate()
+        .map(|(id, (accepting, transitions))| StateRow { accepting: *accepting, state_id: id as u32, transitions: transitions.clone() })
+        .collect();
+    let alphabet: HashMap<u8, usize> = alphabet.iter().enumerate().map(|(i, &b)| (b, i)).collect();
+    DFA::with_start_state(states, alphabet, start).unwrap()
+}
+
+// walk the table directly so a test doesn't depend on the code it checks. without an alphabet each byte of
+// input is a column number
+pub fn accepts(dfa: &DFA, input: &[u8]) -> bool {
+    let mut state = dfa.start_state;
+    for b in input {
+        let column = if dfa.alphabet.is_empty() { Some(*b as usize) } else { dfa.alphabet.get(b).copied() };
+        match column.and_then(|c| dfa.states[state].transitions.get(c).copied().flatten()) {
+            Some(next) => state = next as usize,
+            None => return false,
+        }
+    }
+    dfa.states[state].accepting
+}
@@ -0,0 +1,53 @@
+use std::collections::{HashMap, HashSet};
+
+use automata::nfa::{NfaStateRow, NFA};
+
+mod common;
+use common::dfa_from_rows;
+
+#[test]
+fn parallel_edges_become_one_class() {
+    // one or more of a..f, or a single z
+    let alphabet = b"abcdefz";
+    let dfa = dfa_from_rows(&[
+        (false, vec![Some(1), Some(1), Some(1), Some(1), Some(1), Some(1), Some(2)]),
+        (true, vec![Some(1), Some(1), Some(1), Some(1), Some(1), Some(1), None]),
+        (true, vec![None; 7]),
+    ], alphabet);
+    let dot = dfa.to_dot();
+    assert!(dot.starts_with("digraph DFA {"));
+    assert!(dot.contains("__start -> 0;"));
+    assert!(dot.contains("0 [shape=circle];"));
+    assert!(dot.contains("1 [shape=doublecircle];"));
+    assert!(dot.contains("0 -> 1 [label=\"[a-f]\"];"));
+    assert!(dot.contains("0 -> 2 [label=\"z\"];"));
+    assert!(dot.contains("1 -> 1 [label=\"[a-f]\"];"));
+    assert!(!dot.contains("2 ->"));
+}
+
+#[test]
+fn symbols_are_alphabet_encoded() {
+    let dfa = dfa_from_rows(&[(false, vec![Some(1), Some(1), Some(1)]), (true, vec![None; 3])], b" -x");
+    assert!(dfa.to_dot().contains("0 -> 1 [label=\"[x20x2dx78]\"];"));
+}
+
+#[test]
+fn groups_share_a_fill_colour() {
+    let dfa = dfa_from_rows(&[(false, vec![Some(1)]), (true, vec![Some(1)])], b"a");
+    let dot = dfa.to_dot_with_groups(&[Some(0), Some(0)]);
+    assert_eq!(dot.matches("fillcolor=lightblue").count(), 2);
+}
+
+#[test]
+fn nfa_edges_fan_out() {
+    let states = vec![
+        NfaStateRow { accepting: false, state_id: 0, transitions: vec![HashSet::from([0, 1]), HashSet::new()] },
+        NfaStateRow { accepting: true, state_id: 1, transitions: vec![HashSet::new(), HashSet::from([1])] },
+    ];
+    let alphabet = HashMap::from([(b'a', 0), (b'b', 1)]);
+    let dot = NFA::new(states, alphabet, 0).unwrap().to_dot();
+    assert!(dot.starts_with("digraph NFA {"));
+    assert!(dot.contains("0 -> 0 [label=\"a\"];"));
+    assert!(dot.contains("0 -> 1 [label=\"a\"];"));
+    assert!(dot.contains("1 -> 1 [label=\"b\"];"));
+}
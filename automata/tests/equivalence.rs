@@ -0,0 +1,90 @@
+use automata::dfa::{MinimizeAlgorithm, DFA};
+
+mod common;
+use common::{dfa_from_rows, dfa_from_rows_with_start};
+
+// (ab)*
+fn ab_star() -> DFA {
+    dfa_from_rows(&[(true, vec![Some(1), None]), (false, vec![None, Some(0)])], b"ab")
+}
+
+#[test]
+fn a_dfa_is_equivalent_to_its_minimization() {
+    // (ab)* with a redundant copy of both states
+    let redundant = dfa_from_rows(&[
+        (true, vec![Some(1), None]),
+        (false, vec![None, Some(2)]),
+        (true, vec![Some(3), None]),
+        (false, vec![None, Some(0)]),
+    ], b"ab");
+    for algorithm in [MinimizeAlgorithm::Hopcroft, MinimizeAlgorithm::StackRefinement] {
+        let mut minimized = redundant.clone();
+        minimized.minimize_with(algorithm);
+        assert_eq!(redundant.equivalent(&minimized), Ok(()));
+    }
+    assert_eq!(redundant.equivalent(&ab_star()), Ok(()));
+}
+
+#[test]
+fn counterexample_is_a_shortest_difference() {
+    // (ab)*a? also accepts strings ending in a
+    let with_trailing_a = dfa_from_rows(&[(true, vec![Some(1), None]), (true, vec![None, Some(0)])], b"ab");
+    assert_eq!(ab_star().equivalent(&with_trailing_a), Err(b"a".to_vec()));
+    assert_eq!(with_trailing_a.equivalent(&ab_star()), Err(b"a".to_vec()));
+
+    // only differ on abab
+    let up_to_one_ab = dfa_from_rows(&[
+        (true, vec![Some(1), None]),
+        (false, vec![None, Some(2)]),
+        (true, vec![None, None]),
+    ], b"ab");
+    assert_eq!(ab_star().equivalent(&up_to_one_ab), Err(b"abab".to_vec()));
+}
+
+#[test]
+fn empty_string_counterexample() {
+    let accepts_nothing = dfa_from_rows(&[(false, vec![None, None])], b"ab");
+    assert_eq!(ab_star().equivalent(&accepts_nothing), Err(Vec::new()));
+}
+
+#[test]
+fn columns_are_matched_by_byte() {
+    // same language, columns listed in the other order
+    let swapped = dfa_from_rows(&[(true, vec![None, Some(1)]), (false, vec![Some(0), None])], b"ba");
+    assert_eq!(ab_star().equivalent(&swapped), Ok(()));
+
+    // a byte only one table knows about goes to the dead state in the other
+    let with_c = dfa_from_rows(&[(true, vec![Some(1), None, Some(0)]), (false, vec![None, Some(0), None])], b"abc");
+    assert_eq!(ab_star().equivalent(&with_c), Err(b"c".to_vec()));
+}
+
+#[test]
+fn distinguishing_suffixes_are_shortest() {
+    // a(b|c) where b and c go to two accepting states with the same future, so those two stay equivalent
+    let dfa = dfa_from_rows(&[
+        (false, vec![Some(1), None, None]),
+        (false, vec![None, Some(2), Some(3)]),
+        (true, vec![None, None, None]),
+        (true, vec![None, None, None]),
+    ], b"abc");
+    assert_eq!(dfa.distinguishing_suffix(0, 1), Some(b"b".to_vec()));
+    assert_eq!(dfa.distinguishing_suffix(1, 2), Some(Vec::new()));
+    assert_eq!(dfa.distinguishing_suffix(0, 0), None);
+    assert_eq!(dfa.distinguishing_suffix(2, 3), None);
+}
+
+#[test]
+fn counterexamples_are_walked_from_the_start_state() {
+    // DFAMINIMIZE --start=1 --verify=b.tt: tables without an alphabet, where state 0 isn't the start
+    let a = dfa_from_rows_with_start(&[
+        (false, vec![Some(0), Some(0)]),
+        (true, vec![Some(2), None]),
+        (false, vec![None, Some(1)]),
+    ], b"", 1);
+    let b = dfa_from_rows(&[(true, vec![None, None])], b"");
+
+    let counterexample = a.equivalent(&b).unwrap_err();
+    assert_eq!(counterexample, [0, 1]);
+    assert!(a.accepts_counterexample(&counterexample));
+    assert!(!b.accepts_counterexample(&counterexample));
+}
@@ -0,0 +1,120 @@
+use automata::dfa::DFA;
+
+mod common;
+use common::dfa_from_rows;
+
+// one or more of i, f, z
+fn identifiers() -> DFA {
+    dfa_from_rows(&[(false, vec![Some(1), Some(1), Some(1)]), (true, vec![Some(1), Some(1), Some(1)])], b"zif")
+}
+
+#[test]
+fn shortlex_order_by_byte() {
+    let strings: Vec<String> = identifiers().enumerate(2).collect();
+    assert_eq!(strings, ["f", "i", "z", "ff", "fi", "fz", "if", "ii", "iz", "zf", "zi", "zz"]);
+}
+
+#[test]
+fn empty_string_and_encoded_symbols() {
+    // (x20 x0a)*, columns listed newline first
+    let dfa = dfa_from_rows(&[(true, vec![None, Some(1)]), (false, vec![Some(0), None])], b"\n ");
+    let strings: Vec<String> = dfa.enumerate(4).collect();
+    assert_eq!(strings, ["", "x20x0a", "x20x0ax20x0a"]);
+}
+
+#[test]
+fn enumeration_is_lazy() {
+    // every string over all 256 bytes: far too many to list up to length 8, but the first few come right away
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let dfa = dfa_from_rows(&[(true, vec![Some(0); 256])], &all_bytes);
+    let first: Vec<String> = dfa.enumerate(8).take(3).collect();
+    assert_eq!(first, ["", "x00", "x01"]);
+}
+
+#[test]
+fn branches_that_accept_too_early_are_skipped() {
+    // aa|ba*ba. the accepting state has no way on, so for every length most branches get within reach of it
+    // and then stop short. those must not show up, or turn into extra strings
+    let dfa = dfa_from_rows(&[
+        (false, vec![Some(1), Some(3)]),
+        (false, vec![Some(2), None]),
+        (true, vec![None, None]),
+        (false, vec![Some(3), Some(1)]),
+    ], b"ab");
+    assert_eq!(dfa.enumerate(5).collect::<Vec<_>>(), ["aa", "bba", "baba", "baaba"]);
+    assert_eq!(dfa.enumerate(40).count() as u128, dfa.count_accepted(40).iter().sum::<u128>());
+}
+
+#[test]
+fn counts_match_enumeration() {
+    let dfa = identifiers();
+    assert_eq!(dfa.count_accepted(4), vec![0, 3, 9, 27, 81]);
+
+    let strings: Vec<String> = dfa.enumerate(4).collect();
+    assert_eq!(strings.len() as u128, dfa.count_accepted(4).iter().sum::<u128>());
+
+    // the keyword if, with a dead end branch that never accepts
+    let keyword = dfa_from_rows(&[
+        (false, vec![Some(3), Some(1), None]),
+        (false, vec![None, None, Some(2)]),
+        (true, vec![None, None, None]),
+        (false, vec![Some(3), None, None]),
+    ], b"zif");
+    assert_eq!(keyword.count_accepted(3), vec![0, 0, 1, 0]);
+    assert_eq!(keyword.enumerate(10).collect::<Vec<_>>(), ["if"]);
+}
+
+#[test]
+fn counts_saturate() {
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let dfa = dfa_from_rows(&[(true, vec![Some(0); 256])], &all_bytes);
+    let counts = dfa.count_accepted(20);
+    assert_eq!(counts[15], 1u128 << 120);
+    assert_eq!(counts[20], u128::MAX);
+}
+
+#[test]
+fn emptiness_and_the_empty_string() {
+    let nothing = dfa_from_rows(&[(false, vec![Some(0), None, None]), (true, vec![None, None, None])], b"zif");
+    assert!(nothing.is_empty());
+    assert!(nothing.is_finite());
+    assert_eq!((nothing.shortest_accepted(), nothing.longest_accepted()), (None, None));
+
+    let only_empty = dfa_from_rows(&[(true, vec![None, None, None])], b"zif");
+    assert!(!only_empty.is_empty());
+    assert!(only_empty.accepts_empty_string());
+    assert_eq!(only_empty.longest_accepted(), Some(Vec::new()));
+    assert!(!identifiers().accepts_empty_string());
+}
+
+#[test]
+fn finite_languages_have_a_longest_string() {
+    // if, iff or a dead end z loop that never accepts
+    let keywords = dfa_from_rows(&[
+        (false, vec![Some(4), Some(1), None]),
+        (false, vec![None, None, Some(2)]),
+        (true, vec![None, None, Some(3)]),
+        (true, vec![None, None, None]),
+        (false, vec![Some(4), None, None]),
+    ], b"zif");
+    assert!(keywords.is_finite());
+    assert_eq!(keywords.shortest_accepted(), Some(b"if".to_vec()));
+    assert_eq!(keywords.longest_accepted(), Some(b"iff".to_vec()));
+
+    assert!(!identifiers().is_finite());
+    assert_eq!(identifiers().longest_accepted(), None);
+    // smallest byte first among the shortest
+    assert_eq!(identifiers().shortest_accepted(), Some(b"f".to_vec()));
+}
+
+#[test]
+fn universality() {
+    let everything = dfa_from_rows(&[(true, vec![Some(0), Some(0)])], b"ab");
+    assert!(everything.is_universal());
+    // an unreachable state that rejects doesn't matter
+    let with_unreachable = dfa_from_rows(&[(true, vec![Some(0), Some(0)]), (false, vec![None, None])], b"ab");
+    assert!(with_unreachable.is_universal());
+    assert!(!identifiers().is_universal());
+    assert!(!identifiers().complement().is_empty());
+    assert!(everything.complement().is_empty());
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::thread;
+
+use automata::dfa::DFA;
+
+mod common;
+use common::dfa_from_rows;
+
+// (ab)+
+fn ab_plus() -> DFA {
+    dfa_from_rows(&[
+        (false, vec![Some(1), None]),
+        (false, vec![None, Some(2)]),
+        (true, vec![Some(1), None]),
+    ], b"ab")
+}
+
+#[test]
+fn longest_accepted_prefix() {
+    let dfa = ab_plus();
+    assert_eq!(dfa.longest_match(b"ababa"), Some(4));
+    assert_eq!(dfa.longest_match(b"abb"), Some(2));
+    assert_eq!(dfa.longest_match(b"a"), None);
+    assert_eq!(dfa.longest_match(b""), None);
+    // a byte outside the alphabet ends the match instead of failing
+    assert_eq!(dfa.longest_match(b"ab!ab"), Some(2));
+}
+
+#[test]
+fn accepting_start_state_matches_empty_prefix() {
+    // (ab)*
+    let dfa = dfa_from_rows(&[(true, vec![Some(1), None]), (false, vec![None, Some(0)])], b"ab");
+    assert_eq!(dfa.longest_match(b"b"), Some(0));
+    assert_eq!(dfa.longest_match(b"aba"), Some(2));
+}
+
+#[test]
+fn agrees_with_simulate() {
+    let mut dfa = ab_plus();
+    for input in [&b"ab"[..], b"abab", b"aab", b"ba", b"ababab"] {
+        let accepted = dfa.simulate(input).unwrap();
+        let length = dfa.get_longest_accepting_match().len();
+        assert_eq!(dfa.longest_match(input).filter(|&n| n > 0), accepted.then_some(length), "{:?}", input);
+    }
+}
+
+#[test]
+fn shared_between_threads() {
+    let dfa = Arc::new(ab_plus());
+    let handles: Vec<_> = (1..=4).map(|n| {
+        let dfa = Arc::clone(&dfa);
+        thread::spawn(move || dfa.longest_match(&b"ab".repeat(n)))
+    }).collect();
+    for (n, handle) in (1..=4).zip(handles) {
+        assert_eq!(handle.join().unwrap(), Some(2 * n));
+    }
+}
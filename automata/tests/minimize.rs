@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use automata::dfa::{MinimizeAlgorithm, StateRow, DFA};
+use automata::random::{random_dfa, random_symbols, RandomSpec};
+
+mod common;
+use common::{accepts, dfa_from_rows};
+
+// every string over symbols up to max_len, shortest first
+fn all_strings(symbols: &[u8], max_len: usize) -> Vec<Vec<u8>> {
+    let mut strings = vec![Vec::new()];
+    let mut frontier = vec![Vec::new()];
+    for _ in 0..max_len {
+        let mut next = Vec::new();
+        for s in &frontier {
+            for &c in symbols {
+                let mut longer = s.clone();
+                longer.push(c);
+                next.push(longer);
+            }
+        }
+        strings.extend(next.iter().cloned());
+        frontier = next;
+    }
+    strings
+}
+
+#[test]
+fn hopcroft_and_stack_refinement_agree() {
+    for seed in 0..200 {
+        // seeded so failures can be reproduced
+        let original = random_dfa(&RandomSpec { states: 8, alphabet_size: 3, ..RandomSpec::default() }, seed);
+        let mut hopcroft = original.clone();
+        hopcroft.minimize_with(MinimizeAlgorithm::Hopcroft);
+        let mut stack = original.clone();
+        stack.minimize_with(MinimizeAlgorithm::StackRefinement);
+
+        // both come out minimal and canonicalized, so the tables are the same
+        assert_eq!(hopcroft.print(), stack.print(), "seed {}", seed);
+        for input in all_strings(&random_symbols(3), 6) {
+            let expected = accepts(&original, &input);
+            assert_eq!(accepts(&hopcroft, &input), expected, "seed {} input {:?}", seed, input);
+            assert_eq!(accepts(&stack, &input), expected, "seed {} input {:?}", seed, input);
+        }
+    }
+}
+
+#[test]
+fn merges_states_that_only_reach_equivalent_states() {
+    // 1 and 2 go to different but equivalent accepting states, so all of 1..=4 collapse to two classes
+    let mut dfa = dfa_from_rows(&[
+        (false, vec![Some(1), Some(2)]),
+        (false, vec![Some(3), None]),
+        (false, vec![Some(4), None]),
+        (true, vec![None, None]),
+        (true, vec![None, None]),
+    ], b"");
+    dfa.minimize();
+    assert_eq!(dfa.size(), 3);
+    assert!(accepts(&dfa, &[0, 0]) && accepts(&dfa, &[1, 0]));
+    assert!(!accepts(&dfa, &[0]) && !accepts(&dfa, &[0, 1]));
+}
+
+#[test]
+fn stack_refinement_merges_states_that_loop_to_themselves() {
+    // 1 and 2 are both a*, each looping on itself, so their rows never match exactly
+    let rows = [
+        (false, vec![Some(1), Some(2)]),
+        (true, vec![Some(1), None]),
+        (true, vec![Some(2), None]),
+        (false, vec![Some(3), Some(3)]),
+    ];
+    for algorithm in [MinimizeAlgorithm::Hopcroft, MinimizeAlgorithm::StackRefinement] {
+        let mut dfa = dfa_from_rows(&rows, b"");
+        assert_eq!(dfa.minimize_with(algorithm), vec![Some(0), Some(1), Some(1), None], "{:?}", algorithm);
+        assert_eq!(dfa.print(), "- 0 1 1 \n+ 1 1 E \n", "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn drops_unreachable_and_dead_states() {
+    let mut dfa = dfa_from_rows(&[
+        (false, vec![Some(1), Some(2)]),
+        (true, vec![None, None]),
+        // dead: can never reach an accepting state
+        (false, vec![Some(2), Some(2)]),
+        // unreachable
+        (true, vec![Some(0), Some(3)]),
+    ], b"");
+    dfa.minimize();
+    assert_eq!(dfa.print(), "- 0 1 E \n+ 1 E E \n");
+}
+
+#[test]
+fn empty_language_keeps_a_start_state() {
+    let mut dfa = dfa_from_rows(&[(false, vec![Some(1)]), (false, vec![Some(0)])], b"");
+    dfa.minimize();
+    assert_eq!(dfa.print(), "- 0 E \n");
+}
+
+#[test]
+fn thousands_of_states_over_a_full_byte_alphabet() {
+    // a cycle of 4000 states on every byte, accepting every 4th: only 4 states are really distinct
+    let n = 4000u32;
+    let rows: Vec<(bool, Vec<Option<u32>>)> = (0..n)
+        .map(|i| (i % 4 == 0, (0..256).map(|c| Some((i + 1 + (c % 2) * 4) % n)).collect()))
+        .collect();
+    let mut dfa = dfa_from_rows(&rows, b"");
+    dfa.minimize();
+
+    assert_eq!(dfa.size(), 4);
+    assert!(dfa.states[0].accepting);
+    for (i, row) in dfa.states.iter().enumerate() {
+        assert!(row.transitions.iter().all(|t| *t == Some(((i + 1) % 4) as u32)));
+    }
+}
+
+#[test]
+fn start_state_does_not_have_to_be_row_zero() {
+    // rows 0 and 1 are (ab)* with a redundant copy in rows 2 and 3. the start is row 2
+    let states = vec![
+        StateRow { accepting: true, state_id: 0, transitions: vec![Some(1), None] },
+        StateRow { accepting: false, state_id: 1, transitions: vec![None, Some(2)] },
+        StateRow { accepting: true, state_id: 2, transitions: vec![Some(3), None] },
+        StateRow { accepting: false, state_id: 3, transitions: vec![None, Some(0)] },
+    ];
+    let original = DFA::with_start_state(states, HashMap::new(), 2).unwrap();
+    for algorithm in [MinimizeAlgorithm::Hopcroft, MinimizeAlgorithm::StackRefinement] {
+        let mut minimized = original.clone();
+        minimized.minimize_with(algorithm);
+        assert_eq!(minimized.equivalent(&original), Ok(()), "{:?}", algorithm);
+        for input in all_strings(&[0, 1], 5) {
+            assert_eq!(accepts(&minimized, &input), accepts(&original, &input), "{:?} {:?}", algorithm, input);
+        }
+    }
+
+    let mut minimized = original.clone();
+    minimized.minimize();
+    assert_eq!((minimized.size(), minimized.start_state), (2, 0));
+    // the start class is named after the state it was reached from
+    assert_eq!(minimized.original_ids, vec![2, 3]);
+}
+
+#[test]
+fn equivalent_inputs_print_identically() {
+    // (ab)*b? written three ways: minimal with shuffled rows, with a redundant copy, and with an unreachable state
+    let shuffled = DFA::with_start_state(vec![
+        StateRow { accepting: false, state_id: 0, transitions: vec![None, Some(2)] },
+        StateRow { accepting: true, state_id: 1, transitions: vec![None, None] },
+        StateRow { accepting: true, state_id: 2, transitions: vec![Some(0), Some(1)] },
+    ], HashMap::new(), 2).unwrap();
+    let redundant = dfa_from_rows(&[
+        (true, vec![Some(1), Some(4)]),
+        (false, vec![None, Some(2)]),
+        (true, vec![Some(3), Some(4)]),
+        (false, vec![None, Some(0)]),
+        (true, vec![None, None]),
+    ], b"");
+    let unreachable = dfa_from_rows(&[
+        (true, vec![Some(2), Some(3)]),
+        (false, vec![Some(1), None]),
+        (false, vec![None, Some(0)]),
+        (true, vec![None, None]),
+    ], b"");
+
+    let mut printed = Vec::new();
+    for dfa in [shuffled, redundant, unreachable] {
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        printed.push(minimized.print());
+    }
+    assert_eq!(printed[0], "+ 0 1 2 \n- 1 E 0 \n+ 2 E E \n");
+    assert_eq!(printed[1], printed[0]);
+    assert_eq!(printed[2], printed[0]);
+}
+
+#[test]
+fn canonical_order_follows_columns_from_the_start() {
+    let mut dfa = DFA::with_start_state(vec![
+        StateRow { accepting: true, state_id: 0, transitions: vec![None, None] },
+        StateRow { accepting: false, state_id: 1, transitions: vec![Some(0), Some(2)] },
+        StateRow { accepting: false, state_id: 2, transitions: vec![Some(2), Some(0)] },
+        StateRow { accepting: false, state_id: 3, transitions: vec![None, None] },
+    ], HashMap::new(), 1).unwrap();
+    dfa.canonicalize();
+    // start first, then what column 0 reaches, then column 1. the unreachable state stays last
+    assert_eq!(dfa.print(), "- 0 1 2 \n+ 1 E E \n- 2 2 1 \n- 3 E E \n");
+    assert_eq!(dfa.original_ids, vec![1, 0, 2, 3]);
+}
+
+#[test]
+fn minimizing_reports_where_each_state_went() {
+    // (ab)*b? with a redundant copy of the first two states and an unreachable state 5
+    let rows = [
+        (true, vec![Some(1), Some(4)]),
+        (false, vec![None, Some(2)]),
+        (true, vec![Some(3), Some(4)]),
+        (false, vec![None, Some(0)]),
+        (true, vec![None, None]),
+        (false, vec![Some(0), Some(5)]),
+    ];
+    let mut dfa = dfa_from_rows(&rows, b"");
+    assert_eq!(dfa.minimize(), vec![Some(0), Some(1), Some(0), Some(1), Some(2), None]);
+
+    // whatever the algorithm, an old state and the state it became accept the same strings
+    for algorithm in [MinimizeAlgorithm::Hopcroft, MinimizeAlgorithm::StackRefinement] {
+        let original = dfa_from_rows(&rows, b"");
+        let mut minimized = original.clone();
+        let merged = minimized.minimize_with(algorithm);
+        assert_eq!(merged.len(), original.size());
+        for (old, new) in merged.iter().enumerate() {
+            let Some(new) = new else { continue };
+            let (mut from_old, mut from_new) = (original.clone(), minimized.clone());
+            from_old.start_state = old;
+            from_new.start_state = *new as usize;
+            for input in all_strings(&[0, 1], 5) {
+                assert_eq!(accepts(&from_old, &input), accepts(&from_new, &input), "{:?} from {} and {}", input, old, new);
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use automata::dfa::{StateRow, DFA};
+use automata::nfa::{NfaStateRow, NFA};
+use automata::random::{random_nfa, RandomSpec};
+
+// each cell is a list of destinations. the last column is lambda when there is one more column than symbols
+fn nfa_from_rows(rows: &[(bool, Vec<Vec<u32>>)], alphabet: &[u8]) -> NFA {
+    let states = rows.iter().enumerate()
+        .map(|(id, (accepting, cells))| NfaStateRow {
+            accepting: *accepting,
+            state_id: id as u32,
+            transitions: cells.iter().map(|cell| cell.iter().copied().collect()).collect(),
+        })
+        .collect();
+    let alphabet: HashMap<u8, usize> = alphabet.iter().enumerate().map(|(i, &b)| (b, i)).collect();
+    NFA::new(states, alphabet, 0).unwrap()
+}
+
+// (a|b)*b with lambda moves: 0 -λ-> 1, 1 -a,b-> 1, 1 -b-> 2, 2 -λ-> 3 accepting
+fn ends_in_b() -> NFA {
+    nfa_from_rows(&[
+        (false, vec![vec![], vec![], vec![1]]),
+        (false, vec![vec![1], vec![1, 2], vec![]]),
+        (false, vec![vec![], vec![], vec![3]]),
+        (true, vec![vec![], vec![], vec![]]),
+    ], b"ab")
+}
+
+#[test]
+fn the_last_column_is_lambda_only_with_an_alphabet() {
+    assert_eq!(ends_in_b().lambda_column, Some(2));
+    let no_alphabet = nfa_from_rows(&[(true, vec![vec![0], vec![0], vec![0]])], b"");
+    assert_eq!(no_alphabet.lambda_column, None);
+    let no_lambda = nfa_from_rows(&[(true, vec![vec![0], vec![0]])], b"ab");
+    assert_eq!(no_lambda.lambda_column, None);
+}
+
+#[test]
+fn lambda_closure_follows_chains() {
+    let nfa = nfa_from_rows(&[
+        (false, vec![vec![], vec![1]]),
+        (false, vec![vec![], vec![2, 0]]),
+        (false, vec![vec![3], vec![]]),
+        (true, vec![vec![], vec![]]),
+    ], b"a");
+    assert_eq!(nfa.follow_lambda(&HashSet::from([0])), HashSet::from([0, 1, 2]));
+    assert_eq!(nfa.follow_lambda(&HashSet::from([3])), HashSet::from([3]));
+    assert_eq!(nfa.follow_char(&HashSet::from([0, 1, 2]), 0), HashSet::from([3]));
+}
+
+#[test]
+fn subset_construction_builds_the_dfa_and_remembers_the_sets() {
+    let (dfa, sets) = ends_in_b().subset_construction();
+    // state 0 is only left through lambda, so the start set {0, 1} is never reached again
+    assert_eq!(dfa.print(), "- 0 1 2 \n- 1 1 2 \n+ 2 1 2 \n");
+    assert_eq!(sets, vec![BTreeSet::from([0, 1]), BTreeSet::from([1]), BTreeSet::from([1, 2, 3])]);
+
+    let expected = DFA::new(vec![
+        StateRow { accepting: false, state_id: 0, transitions: vec![Some(0), Some(1)] },
+        StateRow { accepting: true, state_id: 1, transitions: vec![Some(0), Some(1)] },
+    ], HashMap::from([(b'a', 0), (b'b', 1)])).unwrap();
+    assert_eq!(dfa.equivalent(&expected), Ok(()));
+}
+
+#[test]
+fn subset_construction_agrees_with_walking_the_nfa() {
+    let spec = RandomSpec { states: 6, alphabet_size: 2, density: 0.5, accepting_ratio: 0.3, lambda_ratio: 0.3 };
+    for seed in 0..30 {
+        let nfa = random_nfa(&spec, seed);
+        let (dfa, _) = nfa.subset_construction();
+
+        // every string over ab up to 5 long, checked against the set of states the nfa can be in
+        let mut strings: Vec<Vec<u8>> = vec![Vec::new()];
+        for len in 0..5 {
+            let longer: Vec<Vec<u8>> = strings.iter()
+                .filter(|s| s.len() == len)
+                .flat_map(|s| [b'a', b'b'].map(|c| [s.clone(), vec![c]].concat()))
+                .collect();
+            strings.extend(longer);
+        }
+        for input in strings {
+            let mut current = nfa.follow_lambda(&HashSet::from([nfa.start_state_id]));
+            for &c in &input {
+                current = nfa.follow_lambda(&nfa.follow_char(&current, nfa.alphabet[&c]));
+            }
+            let nfa_accepts = current.iter().any(|&s| nfa.state_is_accepting(s));
+            assert_eq!(dfa.longest_match(&input) == Some(input.len()), nfa_accepts, "seed {} on {:?}", seed, input);
+        }
+    }
+}
+
+#[test]
+fn simulation_follows_lambda_at_the_start_and_after_each_step() {
+    // a | a*b, each branch entered through lambda from state 0: 1 -a-> 2 accepting, 3 -a-> 3, 3 -b-> 4 accepting
+    let mut nfa = nfa_from_rows(&[
+        (false, vec![vec![], vec![], vec![1, 3]]),
+        (false, vec![vec![2], vec![], vec![]]),
+        (true, vec![vec![], vec![], vec![]]),
+        (false, vec![vec![3], vec![4], vec![]]),
+        (true, vec![vec![], vec![], vec![]]),
+    ], b"ab");
+
+    assert!(nfa.simulate(b"a").unwrap());
+    assert_eq!(nfa.get_longest_accepting_match(), b"a".to_vec());
+    // the a branch dies after one symbol, the a*b branch carries on and accepts the whole thing
+    assert!(nfa.simulate(b"aaab").unwrap());
+    assert_eq!(nfa.get_longest_accepting_match(), b"aaab".to_vec());
+    // the trailing a has nowhere to go, so the match stops at aab
+    assert!(nfa.simulate(b"aaba").unwrap());
+    assert_eq!(nfa.get_longest_accepting_match(), b"aab".to_vec());
+    assert!(nfa.simulate(b"bb").unwrap());
+    assert_eq!(nfa.get_longest_accepting_match(), b"b".to_vec());
+    assert!(!nfa.simulate(b"").unwrap());
+
+    assert_eq!(nfa.longest_match(b"aab"), Some(3));
+    assert_eq!(nfa.longest_match(b"aa"), Some(1));
+    assert_eq!(nfa.longest_match(b"c"), None);
+}
+
+#[test]
+fn an_accepting_state_behind_lambda_accepts_the_empty_string() {
+    let nfa = nfa_from_rows(&[(false, vec![vec![], vec![1]]), (true, vec![vec![1], vec![]])], b"a");
+    assert_eq!(nfa.longest_match(b""), Some(0));
+    assert_eq!(nfa.longest_match(b"aa"), Some(2));
+}
+
+#[test]
+fn longest_match_agrees_with_the_subset_construction() {
+    let spec = RandomSpec { states: 8, alphabet_size: 3, density: 0.4, accepting_ratio: 0.3, lambda_ratio: 0.4 };
+    for seed in 0..30 {
+        let mut nfa = random_nfa(&spec, seed);
+        let (dfa, _) = nfa.subset_construction();
+        for input in [&b"abcabc"[..], b"aaaa", b"cbacba", b"bbbcc", b""] {
+            assert_eq!(nfa.longest_match(input), dfa.longest_match(input), "seed {} on {:?}", seed, input);
+            let accepted = nfa.simulate(input).unwrap();
+            let length = nfa.get_longest_accepting_match().len();
+            assert_eq!(nfa.longest_match(input).filter(|&n| n > 0), accepted.then_some(length), "seed {} on {:?}", seed, input);
+        }
+    }
+}
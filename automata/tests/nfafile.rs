@@ -0,0 +1,48 @@
+use automata::error::Error;
+use automata::nfafile::parse_nfa_file;
+
+// (line, column, token) of every problem found
+fn diagnostics(contents: &str) -> Vec<(usize, usize, String)> {
+    match parse_nfa_file("n.nfa", contents) {
+        Err(Error::MalformedTable { diagnostics, .. }) => diagnostics.into_iter().map(|d| (d.line, d.column, d.token)).collect(),
+        other => panic!("expected a malformed table, got {:?}", other.map(|f| f.print())),
+    }
+}
+
+const ENDS_IN_B: &str = "4 L a b\n- 0 E E 1\n- 1 1 1,2 E\n- 2 E E 3\n+ 3 E E E\n";
+
+#[test]
+fn header_gives_the_alphabet_and_lambda() {
+    let file = parse_nfa_file("n.nfa", ENDS_IN_B).unwrap();
+    assert_eq!(file.lambda, b'L');
+    assert_eq!(file.nfa.alphabet.get(&b'a'), Some(&0));
+    assert_eq!(file.nfa.alphabet.get(&b'b'), Some(&1));
+    assert_eq!(file.nfa.lambda_column, Some(2));
+    // rows come back the way NFA::print writes them, with a space after every cell
+    assert_eq!(file.print(), "4 L a b\n- 0 E E 1 \n- 1 1 1,2 E \n- 2 E E 3 \n+ 3 E E E \n");
+
+    let (dfa, _) = file.nfa.subset_construction();
+    assert_eq!(dfa.longest_match(b"abab"), Some(4));
+    assert_eq!(dfa.longest_match(b"aba"), Some(2));
+}
+
+#[test]
+fn symbols_are_alphabet_encoded() {
+    let file = parse_nfa_file("n.nfa", "1 x00 x20ab\n+ 0 0 0 0 E\n").unwrap();
+    assert_eq!(file.lambda, 0);
+    assert_eq!(file.nfa.column_symbols()[..3], *b" ab");
+    assert_eq!(file.print(), "1 x00 x20 a b\n+ 0 0 0 0 E \n");
+}
+
+#[test]
+fn every_problem_is_reported_with_its_line() {
+    assert_eq!(diagnostics("3 a a b\n- 0 E E\n"), vec![(1, 5, "a".to_string())]);
+    assert_eq!(diagnostics("2 L a\n+ 0 0 E\n"), vec![(1, 1, "2".to_string())]);
+    assert_eq!(diagnostics("two xq a\n- 0 E E\n+ 1 E\n"), vec![
+        (1, 1, "two".to_string()),
+        (1, 5, "xq".to_string()),
+        (3, 6, String::new()),
+    ]);
+    // no alphabet, and no rows either
+    assert_eq!(diagnostics("1 L\n"), vec![(1, 1, "1 L".to_string()), (1, 1, String::new())]);
+}
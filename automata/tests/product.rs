@@ -0,0 +1,71 @@
+
+use automata::dfa::DFA;
+use automata::Error;
+
+mod common;
+use common::{accepts, dfa_from_rows};
+
+// identifiers over {i, f, z}: one or more letters
+fn identifiers() -> DFA {
+    dfa_from_rows(&[(false, vec![Some(1), Some(1), Some(1)]), (true, vec![Some(1), Some(1), Some(1)])], b"ifz")
+}
+
+// the keyword `if`, with the columns in a different order than identifiers()
+fn keyword_if() -> DFA {
+    dfa_from_rows(&[
+        (false, vec![None, Some(1), None]),
+        (false, vec![Some(2), None, None]),
+        (true, vec![None, None, None]),
+    ], b"fiz")
+}
+
+const SAMPLES: [&[u8]; 8] = [b"", b"i", b"f", b"if", b"fi", b"iff", b"z", b"zif"];
+
+#[test]
+fn identifiers_minus_keywords() {
+    let ids = identifiers().difference(&keyword_if()).unwrap();
+    for s in SAMPLES {
+        assert_eq!(accepts(&ids, s), !s.is_empty() && s != b"if", "{:?}", s);
+    }
+}
+
+#[test]
+fn union_and_intersection() {
+    let either = keyword_if().union(&identifiers()).unwrap();
+    let both = identifiers().intersect(&keyword_if()).unwrap();
+    for s in SAMPLES {
+        assert_eq!(accepts(&either, s), !s.is_empty(), "{:?}", s);
+        assert_eq!(accepts(&both, s), s == b"if", "{:?}", s);
+    }
+    assert_eq!(both.equivalent(&keyword_if()), Ok(()));
+}
+
+#[test]
+fn complement_flips_every_string() {
+    let not_if = keyword_if().complement();
+    for s in SAMPLES {
+        assert_eq!(accepts(&not_if, s), s != b"if", "{:?}", s);
+    }
+    // complete now, so no E left in the table
+    assert!(not_if.states.iter().all(|s| s.transitions.iter().all(|t| t.is_some())));
+    assert_eq!(not_if.complement().equivalent(&keyword_if()), Ok(()));
+}
+
+#[test]
+fn difference_is_intersection_with_complement() {
+    let direct = identifiers().difference(&keyword_if()).unwrap();
+    let via_complement = identifiers().intersect(&keyword_if().complement()).unwrap();
+    assert_eq!(direct.equivalent(&via_complement), Ok(()));
+}
+
+#[test]
+fn mismatched_alphabets_are_reported() {
+    let other_letters = dfa_from_rows(&[(true, vec![None, None, None])], b"abc");
+    let fewer_letters = dfa_from_rows(&[(true, vec![None, None])], b"if");
+    for other in [other_letters, fewer_letters] {
+        match identifiers().union(&other) {
+            Err(Error::AlphabetMismatch { .. }) => {}
+            result => panic!("expected an alphabet mismatch, got {:?}", result.map(|d| d.print())),
+        }
+    }
+}
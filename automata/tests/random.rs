@@ -0,0 +1,54 @@
+use automata::dfa::MinimizeAlgorithm;
+use automata::random::{random_dfa, random_nfa, RandomSpec};
+use automata::ttfile;
+
+#[test]
+fn the_same_seed_gives_the_same_automaton() {
+    let spec = RandomSpec { states: 12, alphabet_size: 3, ..RandomSpec::default() };
+    assert_eq!(random_dfa(&spec, 7).print(), random_dfa(&spec, 7).print());
+    assert_eq!(random_nfa(&spec, 7).print(), random_nfa(&spec, 7).print());
+    assert_ne!(random_dfa(&spec, 7).print(), random_dfa(&spec, 8).print());
+}
+
+#[test]
+fn generated_tables_load_back() {
+    let spec = RandomSpec { states: 20, alphabet_size: 4, density: 0.6, accepting_ratio: 0.5, lambda_ratio: 0.4 };
+    for seed in 0..20 {
+        let dfa = random_dfa(&spec, seed);
+        let rows = ttfile::parse_dfa_table("random.tt", &dfa.print(), Some(4)).unwrap();
+        assert_eq!(rows.len(), 20);
+
+        // one column per symbol plus lambda
+        let nfa = random_nfa(&spec, seed);
+        let rows = ttfile::parse_nfa_table("random.tt", &nfa.print(), Some(5)).unwrap();
+        assert_eq!(rows.len(), 20);
+    }
+}
+
+#[test]
+fn the_knobs_reach_their_extremes() {
+    let none = RandomSpec { states: 5, alphabet_size: 2, density: 0.0, accepting_ratio: 0.0, lambda_ratio: 0.0 };
+    let dfa = random_dfa(&none, 1);
+    assert!(dfa.states.iter().all(|s| !s.accepting && s.transitions.iter().all(|t| t.is_none())));
+    let nfa = random_nfa(&none, 1);
+    assert!(nfa.states.iter().all(|s| s.transitions.iter().all(|t| t.is_empty())));
+
+    let all = RandomSpec { states: 5, alphabet_size: 2, density: 1.0, accepting_ratio: 1.0, lambda_ratio: 1.0 };
+    let dfa = random_dfa(&all, 1);
+    assert!(dfa.is_universal());
+    let nfa = random_nfa(&all, 1);
+    assert!(nfa.states.iter().all(|s| s.accepting && s.transitions.iter().all(|t| !t.is_empty())));
+}
+
+#[test]
+fn minimizing_random_dfas_keeps_the_language() {
+    let spec = RandomSpec { states: 30, alphabet_size: 3, density: 0.7, accepting_ratio: 0.3, lambda_ratio: 0.0 };
+    for seed in 0..50 {
+        let dfa = random_dfa(&spec, seed);
+        for algorithm in [MinimizeAlgorithm::Hopcroft, MinimizeAlgorithm::StackRefinement] {
+            let mut minimized = dfa.clone();
+            minimized.minimize_with(algorithm);
+            assert_eq!(dfa.equivalent(&minimized), Ok(()), "seed {} with {:?}", seed, algorithm);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use automata::dfa::DFA;
+use automata::error::Error;
+use automata::regex::{parse_regex, regex_to_nfa, RegexAst};
+
+const ALPHABET: &[u8] = b"abc01 \n";
+
+fn minimal_dfa(pattern: &str) -> DFA {
+    let (mut dfa, _) = regex_to_nfa(pattern, ALPHABET).unwrap().subset_construction();
+    dfa.minimize();
+    dfa
+}
+
+// every accepted string up to max_len, alphabet encoded, shortest first
+fn language(pattern: &str, max_len: usize) -> Vec<String> {
+    minimal_dfa(pattern).enumerate(max_len).collect()
+}
+
+fn error_column(pattern: &str) -> usize {
+    match regex_to_nfa(pattern, ALPHABET) {
+        Err(Error::MalformedRegex { column, .. }) => column,
+        other => panic!("expected a bad regex, got {:?}", other.map(|nfa| nfa.print())),
+    }
+}
+
+#[test]
+fn operators() {
+    assert_eq!(language("ab|c", 3), ["c", "ab"]);
+    assert_eq!(language("a(b|c)?", 3), ["a", "ab", "ac"]);
+    assert_eq!(language("(ab)*", 4), ["", "ab", "abab"]);
+    assert_eq!(language("a+b", 3), ["ab", "aab"]);
+    assert_eq!(language("a|", 2), ["", "a"]);
+    assert_eq!(language("()", 2), [""]);
+}
+
+#[test]
+fn classes_dot_and_escapes() {
+    assert_eq!(language("[a-c]", 1), ["a", "b", "c"]);
+    // the range runs over bytes the alphabet doesn't have, only 0 and 1 are left
+    assert_eq!(language("[0-9]1", 2), ["01", "11"]);
+    assert_eq!(language(".", 1).len(), ALPHABET.len());
+    assert_eq!(language("ax20x0a", 3), ["ax20x0a"]);
+    // a literal space is a space, like the alphabet encoding reads it
+    assert_eq!(language("a b", 3), ["ax20b"]);
+}
+
+#[test]
+fn thompson_shape() {
+    let nfa = regex_to_nfa("a*", ALPHABET).unwrap();
+    assert_eq!(nfa.start_state_id, 0);
+    assert_eq!(nfa.lambda_column, Some(ALPHABET.len()));
+    assert_eq!(nfa.states.iter().filter(|s| s.accepting).count(), 1);
+    // the new start, the star's way in and out, and the two states for a
+    assert_eq!(nfa.size(), 5);
+}
+
+#[test]
+fn repeated_alphabet_symbols_share_a_column() {
+    let nfa = regex_to_nfa("b", b"aab").unwrap();
+    assert_eq!(nfa.column_symbols()[..2], *b"ab");
+    assert_eq!(nfa.lambda_column, Some(2));
+    let (mut dfa, _) = nfa.subset_construction();
+    dfa.minimize();
+    assert_eq!(dfa.enumerate(2).collect::<Vec<_>>(), ["b"]);
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    assert_eq!(error_column("(ab"), 4);
+    assert_eq!(error_column("ab)"), 3);
+    assert_eq!(error_column("a|*"), 3);
+    assert_eq!(error_column("[]"), 2);
+    assert_eq!(error_column("[c-a]"), 4);
+    assert_eq!(error_column("abd"), 3);
+    assert_eq!(error_column("x4"), 1);
+    // the alphabet encoding never keeps : or \ literal, so neither does a regex
+    assert_eq!(error_column("a:"), 2);
+    assert_eq!(error_column("[a\\]"), 3);
+    assert_eq!(parse_regex("x3ax5c").unwrap(), RegexAst::Sequence(vec![RegexAst::Symbol(b':'), RegexAst::Symbol(b'\\')]));
+}
+
+#[test]
+fn parses_into_a_tree() {
+    use RegexAst::*;
+    assert_eq!(parse_regex("a|b*").unwrap(), Alternation(vec![Symbol(b'a'), Star(Box::new(Symbol(b'b')))]));
+    assert_eq!(parse_regex("(ab)+?").unwrap(), Optional(Box::new(Plus(Box::new(Sequence(vec![Symbol(b'a'), Symbol(b'b')]))))));
+    // inside a class only ] and - are special
+    assert_eq!(parse_regex("[a-c(*]").unwrap(), Class(vec![(b'a', b'c'), (b'(', b'('), (b'*', b'*')]));
+    assert_eq!(parse_regex("x2a.-").unwrap(), Sequence(vec![Symbol(b'*'), Any, Symbol(b'-')]));
+    assert_eq!(parse_regex("").unwrap(), Empty);
+    // without an alphabet any symbol goes
+    assert_eq!(parse_regex("z").unwrap(), Symbol(b'z'));
+}
+
+#[test]
+fn errors_list_what_was_expected() {
+    let expected = |pattern: &str| match parse_regex(pattern) {
+        Err(Error::MalformedRegex { expected, .. }) => expected,
+        other => panic!("expected a bad regex, got {:?}", other),
+    };
+    assert_eq!(expected("a|*"), ["(", "[", ".", "a symbol", "|", ")", "the end of the regex"]);
+    assert_eq!(expected("(ab"), ["*", "+", "?", "(", "[", ".", "a symbol", "|", ")"]);
+    assert_eq!(expected("[a"), ["-", "a symbol", "]"]);
+    assert_eq!(expected("ab)"), ["*", "+", "?", "(", "[", ".", "a symbol", "|", "the end of the regex"]);
+    let message = parse_regex("[]").unwrap_err().to_string();
+    assert_eq!(message, "bad regex '[]' at column 2: unexpected ']', expected a symbol");
+}
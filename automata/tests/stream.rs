@@ -0,0 +1,102 @@
+use std::io::{self, Read};
+
+use automata::dfa::DFA;
+use automata::stream::{StreamMatcher, StreamTokenizer};
+use automata::Error;
+
+mod common;
+use common::dfa_from_rows;
+
+// hands out its input a few bytes per read, like a slow pipe
+struct Trickle<'a> {
+    input: &'a [u8],
+    step: usize,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.step.min(buf.len()).min(self.input.len());
+        buf[..n].copy_from_slice(&self.input[..n]);
+        self.input = &self.input[n..];
+        Ok(n)
+    }
+}
+
+const ALPHABET: &[u8] = b"ab \n";
+
+// (ab)+
+fn ab_plus() -> DFA {
+    dfa_from_rows(&[
+        (false, vec![Some(1), None, None, None]),
+        (false, vec![None, Some(2), None, None]),
+        (true, vec![Some(1), None, None, None]),
+    ], ALPHABET)
+}
+
+// a+
+fn a_plus() -> DFA {
+    dfa_from_rows(&[(false, vec![Some(1), None, None, None]), (true, vec![Some(1), None, None, None])], ALPHABET)
+}
+
+// runs of spaces and newlines
+fn blanks() -> DFA {
+    dfa_from_rows(&[(false, vec![None, None, Some(1), Some(1)]), (true, vec![None, None, Some(1), Some(1)])], ALPHABET)
+}
+
+#[test]
+fn matcher_keeps_state_across_chunks() {
+    let dfa = ab_plus();
+    let mut matcher = StreamMatcher::new(&dfa, 100);
+    assert_eq!(matcher.feed(b"a"), 1);
+    assert_eq!(matcher.longest_match(), None);
+    assert_eq!(matcher.feed(b"ba"), 2);
+    assert_eq!(matcher.longest_match(), Some(102));
+    assert_eq!(matcher.feed(b"bb"), 1);
+    assert!(matcher.is_dead());
+    assert_eq!(matcher.longest_match(), Some(104));
+    assert_eq!(matcher.position(), 104);
+}
+
+#[test]
+fn tokens_are_the_same_for_every_chunk_size() {
+    let (ab, a, blank) = (ab_plus(), a_plus(), blanks());
+    let recognizers = [&ab, &a, &blank];
+    let input = b"abab aaa\nab  a\n\naba";
+
+    let expected: Vec<_> = StreamTokenizer::new(&input[..], &recognizers).collect::<Result<_, _>>().unwrap();
+    let spans: Vec<(usize, u64, u64)> = expected.iter().map(|t| (t.recognizer, t.start, t.end)).collect();
+    assert_eq!(spans, [
+        (0, 0, 4), (2, 4, 5), (1, 5, 8), (2, 8, 9), (0, 9, 11), (2, 11, 13), (1, 13, 14), (2, 14, 16), (0, 16, 18), (1, 18, 19),
+    ]);
+    assert_eq!((expected[4].line, expected[4].column), (2, 1));
+    assert_eq!((expected[8].line, expected[8].column), (4, 1));
+
+    for step in 1..=5 {
+        for chunk_size in [1, 2, 3, 7] {
+            let tokens: Vec<_> = StreamTokenizer::with_chunk_size(Trickle { input, step }, &recognizers, chunk_size)
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(tokens, expected, "step {} chunk size {}", step, chunk_size);
+        }
+    }
+}
+
+#[test]
+fn no_match_reports_line_and_column() {
+    let (ab, blank) = (ab_plus(), blanks());
+    let mut tokens = StreamTokenizer::with_chunk_size(&b"ab\nabb"[..], &[&ab, &blank], 2);
+    assert_eq!(tokens.next().unwrap().unwrap().bytes, b"ab");
+    assert_eq!(tokens.next().unwrap().unwrap().bytes, b"\n");
+    assert_eq!(tokens.next().unwrap().unwrap().bytes, b"ab");
+    match tokens.next() {
+        Some(Err(Error::NoMatch { line: 2, column: 3 })) => {}
+        other => panic!("expected no match at 2:3, got {:?}", other),
+    }
+    assert!(tokens.next().is_none());
+}
+
+#[test]
+fn empty_input_has_no_tokens() {
+    let ab = ab_plus();
+    assert_eq!(StreamTokenizer::new(&b""[..], &[&ab]).count(), 0);
+}
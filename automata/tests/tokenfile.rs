@@ -0,0 +1,55 @@
+use automata::tokenfile::{compare, normalize, normalize_value};
+use automata::Error;
+
+#[test]
+fn values_get_one_canonical_form() {
+    assert_eq!(normalize_value("x61x5Cx20").unwrap(), "ax5cx20");
+    assert_eq!(normalize_value("x5c").unwrap(), normalize_value("x5C").unwrap());
+}
+
+#[test]
+fn normalize_skips_blank_lines_and_canonicalizes_values() {
+    let tokens = "pqrs x70qr 1 1\n\n   \nIGNORE x0A 1 4\n";
+    assert_eq!(normalize("tokens", tokens).unwrap(), "pqrs pqr 1 1\nIGNORE x0a 1 4\n");
+}
+
+#[test]
+fn compare_ignores_encoding_differences() {
+    let left = "whack x5cooox5c 2 19\n";
+    let right = "whack x5Cx6fox6fx5C 2 19\n\n";
+    assert!(compare("left", left, "right", right).unwrap().is_empty());
+}
+
+#[test]
+fn compare_reports_differing_and_missing_tokens() {
+    let left = "a aa 1 1\nb b 1 3\n";
+    let right = "a aa 1 1\nb b 1 4\nc c 2 1\n";
+    let differences = compare("left", left, "right", right).unwrap();
+
+    assert_eq!(differences.len(), 2);
+    assert_eq!(differences[0].index, 2);
+    assert_eq!(differences[0].left.as_ref().unwrap().column, 3);
+    assert_eq!(differences[0].right.as_ref().unwrap().column, 4);
+    assert_eq!(differences[1].index, 3);
+    assert!(differences[1].left.is_none());
+}
+
+#[test]
+fn malformed_rows_carry_their_line() {
+    match normalize("tokens", "a a 1 1\na x4 1 2\n") {
+        Err(Error::MalformedRow { file, line, .. }) => assert_eq!((file.as_str(), line), ("tokens", 2)),
+        other => panic!("expected a malformed row, got {:?}", other),
+    }
+    assert!(normalize("tokens", "a a 1\n").is_err());
+}
+
+#[test]
+fn one_malformed_row_fails_the_whole_file() {
+    // a missing field, an extra one and a column that isn't a number, each after a good row
+    for bad in ["a a 1", "a a 1 2 3", "a a 1 two"] {
+        match normalize("tokens", &format!("a a 1 1\n{}\nb b 2 1\n", bad)) {
+            Err(Error::MalformedRow { line, .. }) => assert_eq!(line, 2, "{}", bad),
+            other => panic!("expected a malformed row for {:?}, got {:?}", bad, other),
+        }
+    }
+}
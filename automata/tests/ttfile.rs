@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+
+use automata::dfa::{StateIds, DFA};
+use automata::error::Diagnostic;
+use automata::ttfile::{self, parse_dfa_table, parse_nfa_table};
+use automata::Error;
+
+fn diagnostics(result: Result<impl std::fmt::Debug, Error>) -> Vec<(usize, usize, String)> {
+    match result {
+        Err(Error::MalformedTable { diagnostics, .. }) => {
+            diagnostics.into_iter().map(|Diagnostic { line, column, token, .. }| (line, column, token)).collect()
+        }
+        other => panic!("expected a malformed table, got {:?}", other),
+    }
+}
+
+#[test]
+fn blank_lines_are_skipped() {
+    let rows = parse_dfa_table("t.tt", "\n- 0 1 E\n   \n+ 1 E 1\n\n", Some(2)).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].transitions, vec![None, Some(1)]);
+    assert!(rows[1].accepting);
+}
+
+#[test]
+fn every_problem_is_reported_with_its_position() {
+    let table = "- 0 1 E\n+ 2 x 5\n* 2 E\n- 3 E E E\n";
+    assert_eq!(diagnostics(parse_dfa_table("t.tt", table, None)), vec![
+        (1, 5, "1".to_string()),
+        (2, 5, "x".to_string()),
+        (2, 7, "5".to_string()),
+        (3, 1, "*".to_string()),
+        (3, 3, "2".to_string()),
+        (3, 6, String::new()),
+        (4, 9, "E".to_string()),
+    ]);
+}
+
+#[test]
+fn dfa_ids_can_have_gaps_and_any_order() {
+    let rows = parse_dfa_table("t.tt", "+ 7 10 E\n- 10 E 42\n+ 42 10 E\n- 3 7 7\n", None).unwrap();
+    let dfa = DFA::with_start_state(rows, HashMap::from([(b'a', 0), (b'b', 1)]), 3).unwrap();
+    assert_eq!(dfa.start_state, 3);
+    assert_eq!(dfa.original_ids, vec![7, 10, 42, 3]);
+    assert_eq!(dfa.print(), "+ 0 1 E \n- 1 E 2 \n+ 2 1 E \n- 3 0 0 \n");
+    assert_eq!(dfa.print_with(StateIds::Original), "+ 7 10 E \n- 10 E 42 \n+ 42 10 E \n- 3 7 7 \n");
+    // a (ab)* after one leading symbol
+    assert_eq!(dfa.longest_match(b"aabab"), Some(5));
+    assert_eq!(dfa.longest_match(b"b"), Some(1));
+    assert_eq!(dfa.longest_match(b""), None);
+}
+
+#[test]
+fn nfa_ids_still_follow_the_rows() {
+    assert_eq!(diagnostics(parse_nfa_table("n.tt", "- 0 1\n+ 5 0\n", None)), vec![(1, 5, "1".to_string()), (2, 3, "5".to_string())]);
+}
+
+#[test]
+fn width_is_checked_against_the_alphabet() {
+    assert_eq!(diagnostics(parse_dfa_table("t.tt", "- 0 E E\n", Some(3))), vec![(1, 8, String::new())]);
+    assert!(parse_dfa_table("t.tt", "- 0 E E E\n", Some(3)).is_ok());
+}
+
+#[test]
+fn errors_name_file_line_and_column() {
+    let message = parse_dfa_table("scan/id.tt", "- 0 E\n- 1 7\n", None).unwrap_err().to_string();
+    assert_eq!(message, "scan/id.tt:2:5: state 7 does not exist, the table has 2 states (read '7')");
+    let message = parse_dfa_table("empty.tt", "\n\n", None).unwrap_err().to_string();
+    assert_eq!(message, "empty.tt:1:1: table has no states");
+}
+
+#[test]
+fn nfa_cells_hold_destination_lists() {
+    let rows = parse_nfa_table("n.tt", "- 0 0,1 E\n+ 1 E 1\n", Some(2)).unwrap();
+    assert_eq!(rows[0].transitions[0], HashSet::from([0, 1]));
+    assert!(rows[0].transitions[1].is_empty());
+    assert_eq!(diagnostics(parse_nfa_table("n.tt", "- 0 0,a E\n", None)), vec![(1, 5, "0,a".to_string())]);
+    // comma lists are only for NFAs
+    assert_eq!(diagnostics(parse_dfa_table("d.tt", "- 0 0,0\n", None)), vec![(1, 5, "0,0".to_string())]);
+}
+
+#[test]
+fn nfa_start_state_has_to_be_a_row() {
+    let path = std::env::temp_dir().join(format!("ttfile_nfa_start_{}.tt", std::process::id()));
+    std::fs::write(&path, "- 0 1 E\n+ 1 E 0\n").unwrap();
+    let path = path.to_str().unwrap();
+    let alphabet = HashMap::from([(b'a', 0)]);
+
+    assert!(ttfile::load_nfa(path, alphabet.clone(), 1).is_ok());
+    let result = ttfile::load_nfa(path, alphabet, 2);
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(result, Err(Error::UnknownState { state: 2 })), "{:?}", result.map(|nfa| nfa.print()));
+}
@@ -1,108 +1,148 @@
 use std::fs::File;
+use std::io::{self, BufWriter};
 use std::io::prelude::*;
 use std::path::Path;
 use std::env;
 use std::process;
+use std::fs;
 use std::fs::OpenOptions;
+use automata::alphabetencoding;
+use automata::error::{Error, Result};
+use automata::tokenfile::{self, TokenLine};
 
-fn encode(input_path: &str) {
+fn encode(input_path: &str) -> Result<String> {
     // read file
     // create path
     let _path = Path::new(input_path);
-    // let _display = path.display();
+    let missing = |e| Error::MissingFile { path: input_path.to_string(), source: e };
 
     // Open the path in read-only mode
-    let mut file = match File::open(&_path) {
-        Ok(file) => file,
-        Err(_) => process::exit(1),
-    };
+    let mut file = File::open(_path).map_err(missing)?;
 
-    // Read the file contents into a string
-    let mut token_to_encode = String::new();
-    if let Err(_why) = file.read_to_string(&mut token_to_encode) {
-        // eprintln!("ERROR: couldn't read {}: {}", _display, _why);
-        process::exit(1); // exit with non-zero status
-    }
+    // Read the raw file contents. any byte is allowed, it doesn't need to be utf-8
+    let mut token_to_encode = Vec::new();
+    file.read_to_end(&mut token_to_encode).map_err(missing)?;
 
     if token_to_encode.is_empty() {
-        process::exit(1);
+        return Err(Error::EmptyToken);
     }
 
-    // parse the string
-    let bytes = token_to_encode.as_bytes();
-    let mut index = 0;
-    let mut output = String::new();
-
-    while index < bytes.len() {
-        let b = bytes[index];
-
-        // ascii graphics are unprintable, we dont accept whitespace, we dont accept unsafe characters
-        if !b.is_ascii_graphic()
-            || b.is_ascii_whitespace()
-            || b == b':' || b == b'\\' || b == b'x'
-            || !((b'!'..=b'9').contains(&b)
-            || (b';'..=b'[').contains(&b)
-            || (b']'..=b'w').contains(&b)
-            || (b'y'..=b'~').contains(&b)) {
-            // encode in hex
-            output.push_str(&format!("x{:02x}", b));
-        } else {
-            // keep literal
-            output.push(b as char);
-        }
-
-        index += 1;
-    }
-    println!("{}", output);
+    Ok(alphabetencoding::encode(&token_to_encode))
 }
 
-fn decode(encoded_token: &str, output_path: &str) {
-    // Try to create output file, exit 1 on failure
-    let mut output_file = match OpenOptions::new()
+fn decode(encoded_token: &str, output_path: &str) -> Result<()> {
+    let missing = |e| Error::MissingFile { path: output_path.to_string(), source: e };
+
+    // Try to create output file
+    let mut output_file = OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
         .open(output_path)
-    {
-        Ok(f) => f,
-        Err(_) => process::exit(1),
+        .map_err(missing)?;
+
+    let decoded = alphabetencoding::decode(encoded_token)?;
+    output_file.write_all(&decoded).map_err(missing)
+}
+
+fn read_token_file(path: &str) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| Error::MissingFile { path: path.to_string(), source: e })
+}
+
+// canonical form of a whole token file, replaces the external aenorm in normoutput
+fn normalize(token_path: &str) -> Result<String> {
+    tokenfile::normalize(token_path, &read_token_file(token_path)?)
+}
+
+// every token that differs between the two files, printed like `diff` so it's easy to read
+fn compare(left_path: &str, right_path: &str) -> Result<Vec<String>> {
+    let differences = tokenfile::compare(left_path, &read_token_file(left_path)?, right_path, &read_token_file(right_path)?)?;
+
+    let show = |token: &Option<TokenLine>| match token {
+        Some(t) => format!("{} {} {} {}", t.termid, t.value, t.line, t.column),
+        None => "(no token)".to_string(),
     };
+    Ok(differences.iter()
+        .map(|d| format!("token {}\n< {}\n> {}", d.index, show(&d.left), show(&d.right)))
+        .collect())
+}
 
-    let bytes = encoded_token.as_bytes();
-    let mut index = 0;
+#[derive(Clone, Copy)]
+enum StreamMode {
+    Encode,
+    Decode,
+    Verify,
+}
 
-    while index < bytes.len() {
-        if bytes[index] == b'x' {
-            if index + 2 >= bytes.len() {
-                process::exit(1); // malformed token
-            }
+// the offset of the first bad escape, or None if the token is well formed
+fn first_bad_escape(encoded_token: &[u8]) -> Option<usize> {
+    // anything that isn't even utf-8 can't be an encoded token, so that byte is the bad one
+    let token = match std::str::from_utf8(encoded_token) {
+        Ok(t) => t,
+        Err(e) => return Some(e.valid_up_to()),
+    };
+    match alphabetencoding::decode(token) {
+        Err(Error::BadEscape { offset, .. }) => Some(offset),
+        _ => None,
+    }
+}
 
-            let hex_str = &encoded_token[index + 1..index + 3];
-            let byte_val = match u8::from_str_radix(hex_str, 16) {
-                Ok(b) => b,
-                Err(_) => process::exit(1),
-            };
+// one token per line from stdin to stdout, so thousands of tokens take one process
+// a bad line is reported on stderr with its line number and left empty in the output so the lines still match up.
+// when decoding, a token with x0a or x0d in it would break the output into extra lines, so it counts as bad too
+// returns how many lines failed
+fn stream(mode: StreamMode) -> Result<usize> {
+    let mut input = io::stdin().lock();
+    let mut output = BufWriter::new(io::stdout().lock());
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    let mut failures = 0;
 
-            if let Err(_) = output_file.write_all(&[byte_val]) {
-                process::exit(1);
+    while input.read_until(b'\n', &mut line)? > 0 {
+        line_number += 1;
+        // only the line ending (\n or \r\n) is stripped, every other byte is part of the token
+        if line.last() == Some(&b'\n') {
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
             }
+        }
 
-            index += 3;
-        } else {
-            let c = bytes[index];
-            if (c.is_ascii_graphic() || c == b' ') && c != b':' && c != b'\\' && c != b'x' {
-                if let Err(_) = output_file.write_all(&[c]) {
-                    process::exit(1);
-                }
-            } else {
-                process::exit(1); // invalid literal
+        match mode {
+            StreamMode::Encode => {
+                output.write_all(alphabetencoding::encode(&line).as_bytes())?;
             }
-            index += 1;
+            StreamMode::Decode | StreamMode::Verify => match first_bad_escape(&line) {
+                None => {
+                    if let StreamMode::Decode = mode {
+                        // first_bad_escape already checked this is a valid token
+                        let decoded = alphabetencoding::decode(&String::from_utf8_lossy(&line))?;
+                        if decoded.iter().any(|&b| b == b'\n' || b == b'\r') {
+                            failures += 1;
+                            eprintln!("ERROR: line {}: the token decodes to a line break, which can't be written one per line", line_number);
+                        } else {
+                            output.write_all(&decoded)?;
+                        }
+                    } else {
+                        output.write_all(b"ok")?;
+                    }
+                }
+                Some(offset) => {
+                    failures += 1;
+                    eprintln!("ERROR: line {}: bad escape at offset {}", line_number, offset);
+                    if let StreamMode::Verify = mode {
+                        write!(output, "bad {}", offset)?;
+                    }
+                }
+            },
         }
+        output.write_all(b"\n")?;
+        line.clear();
     }
+    output.flush()?;
+    Ok(failures)
 }
 
-
 fn main() {
     let args: Vec<String> = env::args().collect();
 
@@ -117,24 +157,109 @@ fn main() {
         "ENCODE"  | "encode" => {
             // ENCODE <input_file>
             if args.len() != 3 {
-                // println!("ERROR: Argument number\nUsage: ./ALPHABETENCODING ENCODE <input_file>");
+                eprintln!("ERROR: Argument number\nUsage: ./ALPHABETENCODING ENCODE <input_file>");
                 process::exit(1);
             }
             let input_path = &args[2];
             
-            encode(input_path);
+            match encode(input_path) {
+                Ok(encoded) => println!("{}", encoded),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    process::exit(1);
+                }
+            }
         }
 
         "DECODE" | "decode" => {
             // DECODE <single_token> <output_file>
             if args.len() != 4 {
-                // println!("ERROR: Argument number\n./ALPHABETENCODING DECODE <single_token> <input_file>");
+                eprintln!("ERROR: Argument number\nUsage: ./ALPHABETENCODING DECODE <single_token> <output_file>");
                 process::exit(1);
             }
             let encoded_token = &args[2];
             let output_path = &args[3];
             
-            decode(encoded_token, output_path);
+            if let Err(e) = decode(encoded_token, output_path) {
+                eprintln!("ERROR: {}", e);
+                process::exit(1);
+            }
+        }
+
+        "VERIFY" | "verify" => {
+            // VERIFY <single_token>, exits 1 and reports where if it isn't well formed
+            if args.len() != 3 {
+                eprintln!("ERROR: Argument number\nUsage: ./ALPHABETENCODING VERIFY <single_token>");
+                process::exit(1);
+            }
+
+            match first_bad_escape(args[2].as_bytes()) {
+                None => println!("ok"),
+                Some(offset) => {
+                    println!("bad escape at offset {}", offset);
+                    process::exit(1);
+                }
+            }
+        }
+
+        "STREAM" | "stream" => {
+            // STREAM ENCODE|DECODE|VERIFY, one token per line on stdin
+            let mode = match args.get(2).map(|m| m.to_uppercase()) {
+                Some(m) if args.len() == 3 && m == "ENCODE" => StreamMode::Encode,
+                Some(m) if args.len() == 3 && m == "DECODE" => StreamMode::Decode,
+                Some(m) if args.len() == 3 && m == "VERIFY" => StreamMode::Verify,
+                _ => {
+                    eprintln!("ERROR: Argument number\nUsage: ./ALPHABETENCODING STREAM ENCODE|DECODE|VERIFY < tokens");
+                    process::exit(1);
+                }
+            };
+
+            match stream(mode) {
+                Ok(0) => {}
+                Ok(_) => process::exit(1),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        "NORMALIZE" | "normalize" => {
+            // NORMALIZE <token_file>, exits 1 on the first line that isn't <termid> <value> <line> <col>
+            if args.len() != 3 {
+                eprintln!("ERROR: Argument number\nUsage: ./ALPHABETENCODING NORMALIZE <token_file>\nevery non-blank line must be <termid> <value> <line> <col>, any other line fails the whole file");
+                process::exit(1);
+            }
+
+            match normalize(&args[2]) {
+                Ok(normalized) => print!("{}", normalized),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        "COMPARE" | "compare" => {
+            // COMPARE <token_file> <token_file>, exits 1 if they differ
+            if args.len() != 4 {
+                eprintln!("ERROR: Argument number\nUsage: ./ALPHABETENCODING COMPARE <token_file> <token_file>");
+                process::exit(1);
+            }
+
+            match compare(&args[2], &args[3]) {
+                Ok(differences) if differences.is_empty() => {}
+                Ok(differences) => {
+                    for d in differences {
+                        println!("{}", d);
+                    }
+                    process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    process::exit(2);
+                }
+            }
         }
 
         _ => {
@@ -0,0 +1,30 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn stream(mode: &str, input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ALPHABETENCODING"))
+        .args(["STREAM", mode])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn decoded_line_breaks_leave_the_line_empty() {
+    let output = stream("DECODE", b"ax0ab\nc\nx0d\n");
+    assert_eq!(output.stdout, b"\nc\n\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("line 1") && stderr.contains("line 3"), "{}", stderr);
+}
+
+#[test]
+fn crlf_line_endings_are_stripped() {
+    let output = stream("VERIFY", b"abc\r\nx41\r\n");
+    assert_eq!(output.stdout, b"ok\nok\n");
+    assert!(output.status.success());
+}
@@ -1,3 +1,101 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process;
+use automata::alphabetencoding;
+use automata::cli;
+use automata::dfa::DFA;
+use automata::nfafile;
+
+const USAGE: &str = "Usage: cargo run -- [--dfa=<tt_file>] <nfa_file> [<strings_file>]";
+
+// flags go anywhere on the command line, see automata::cli
+struct Options {
+    // write the DFA from subset construction here, in tt format
+    dfa_path: Option<String>,
+}
+
+// splits the command line into flags and positional args
+fn parse_args(args: &[String]) -> std::result::Result<(Options, Vec<String>), String> {
+    let mut options = Options { dfa_path: None };
+    let positional = cli::split_args(args, |arg| {
+        if let Some(path) = arg.strip_prefix("--dfa=") {
+            options.dfa_path = Some(path.to_string());
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+    Ok((options, positional))
+}
+
+// empty strings are written "" so they still show up
+fn show(encoded: &str) -> &str {
+    if encoded.is_empty() { "\"\"" } else { encoded }
+}
+
+// `<string> ACCEPTED|REJECTED <longest accepted prefix>`, both re-encoded canonically so they line up
+// however the input was written. a string that no prefix of is accepted gets `none`
+fn report(dfa: &DFA, input: &[u8]) -> String {
+    let longest = dfa.longest_match(input);
+    let verdict = if longest == Some(input.len()) { "ACCEPTED" } else { "REJECTED" };
+    let prefix = longest.map_or("none".to_string(), |len| show(&alphabetencoding::encode(&input[..len])).to_string());
+    format!("{} {} {}", show(&alphabetencoding::encode(input)), verdict, prefix)
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().collect();
+
+    let (options, positional) = parse_args(&args[1..]).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}\n{}", e, USAGE);
+        process::exit(1);
+    });
+    if positional.is_empty() || positional.len() > 2 {
+        eprintln!("ERROR: Argument number\n{}", USAGE);
+        process::exit(1);
+    }
+
+    let definition = nfafile::load_nfa_file(&positional[0]).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}", e);
+        process::exit(1);
+    });
+    let (dfa, _) = definition.nfa.subset_construction();
+
+    if let Some(path) = &options.dfa_path {
+        if let Err(e) = fs::write(path, dfa.print()) {
+            eprintln!("ERROR: Could not write {}: {}", path, e);
+            process::exit(1);
+        }
+    }
+
+    // candidate strings, one alphabet encoded string per line. from the file if there is one, else stdin
+    let input: Box<dyn Read> = match positional.get(1) {
+        Some(path) => match fs::File::open(path) {
+            Ok(file) => Box::new(file),
+            Err(e) => {
+                eprintln!("ERROR: could not open file '{}': {}", path, e);
+                process::exit(1);
+            }
+        },
+        None => Box::new(io::stdin()),
+    };
+
+    // a bad line is reported and skipped, the rest still get matched
+    let mut failed = false;
+    for (line_index, line) in BufReader::new(input).lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            process::exit(1);
+        });
+        match alphabetencoding::decode(line.trim()) {
+            Ok(decoded) => println!("{}", report(&dfa, &decoded)),
+            Err(e) => {
+                eprintln!("ERROR: line {}: {}", line_index + 1, e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
 }
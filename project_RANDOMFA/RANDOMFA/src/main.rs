@@ -0,0 +1,96 @@
+use std::env;
+use std::fs;
+use std::process;
+use automata::alphabetencoding;
+use automata::cli;
+use automata::random::{random_dfa, random_nfa, random_symbols, RandomSpec};
+
+const USAGE: &str = "Usage: cargo run -- [--nfa] [--seed=<n>] [--states=<n>] [--symbols=<n>] [--density=<p>] [--accepting=<p>] [--lambda=<p>] [--alphabet=<file>] <output_file>";
+
+// flags go anywhere on the command line, see automata::cli
+struct Options {
+    nfa: bool,
+    seed: u64,
+    spec: RandomSpec,
+    // also write the alphabet, encoded like the first line of a scan.u
+    alphabet_path: Option<String>,
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> std::result::Result<T, String> {
+    value.parse().map_err(|_| format!("{} needs a number, got {}", flag, value))
+}
+
+fn parse_ratio(flag: &str, value: &str) -> std::result::Result<f64, String> {
+    let ratio: f64 = parse_number(flag, value)?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(format!("{} must be between 0 and 1, got {}", flag, value));
+    }
+    Ok(ratio)
+}
+
+// splits the command line into flags and positional args
+fn parse_args(args: &[String]) -> std::result::Result<(Options, Vec<String>), String> {
+    let mut options = Options { nfa: false, seed: 0, spec: RandomSpec::default(), alphabet_path: None };
+    let positional = cli::split_args(args, |arg| {
+        if arg == "--nfa" {
+            options.nfa = true;
+        } else if let Some(seed) = arg.strip_prefix("--seed=") {
+            options.seed = parse_number("seed", seed)?;
+        } else if let Some(states) = arg.strip_prefix("--states=") {
+            options.spec.states = parse_number("states", states)?;
+            if options.spec.states == 0 {
+                return Err("states must be at least 1".to_string());
+            }
+        } else if let Some(symbols) = arg.strip_prefix("--symbols=") {
+            options.spec.alphabet_size = parse_number("symbols", symbols)?;
+            if options.spec.alphabet_size > 256 {
+                return Err(format!("symbols can be at most 256, got {}", symbols));
+            }
+        } else if let Some(density) = arg.strip_prefix("--density=") {
+            options.spec.density = parse_ratio("density", density)?;
+        } else if let Some(accepting) = arg.strip_prefix("--accepting=") {
+            options.spec.accepting_ratio = parse_ratio("accepting", accepting)?;
+        } else if let Some(lambda) = arg.strip_prefix("--lambda=") {
+            options.spec.lambda_ratio = parse_ratio("lambda", lambda)?;
+        } else if let Some(path) = arg.strip_prefix("--alphabet=") {
+            options.alphabet_path = Some(path.to_string());
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+    Ok((options, positional))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let (options, positional) = parse_args(&args[1..]).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}\n{}", e, USAGE);
+        process::exit(1);
+    });
+    if positional.len() != 1 {
+        eprintln!("ERROR: Argument number\n{}", USAGE);
+        process::exit(1);
+    }
+    let output_path = &positional[0];
+
+    // the nfa table has one more column than there are symbols, the last one is lambda
+    let table = if options.nfa {
+        random_nfa(&options.spec, options.seed).print()
+    } else {
+        random_dfa(&options.spec, options.seed).print()
+    };
+
+    if let Err(e) = fs::write(output_path, table) {
+        eprintln!("ERROR: Could not write {}: {}", output_path, e);
+        process::exit(1);
+    }
+
+    if let Some(path) = &options.alphabet_path {
+        if let Err(e) = fs::write(path, format!("{}\n", alphabetencoding::encode(&random_symbols(options.spec.alphabet_size)))) {
+            eprintln!("ERROR: Could not write {}: {}", path, e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::env;
+use std::fs;
+use std::process;
+use automata::alphabetencoding;
+use automata::cli;
+use automata::nfafile::NfaFile;
+use automata::regex::regex_to_nfa;
+
+const USAGE: &str = "Usage: cargo run -- [--dfa=<tt_file>] <alphabet_file> <regex> <nfa_file>";
+
+// flags go anywhere on the command line, see automata::cli
+struct Options {
+    // also write the minimized DFA for the regex here, in tt format
+    dfa_path: Option<String>,
+}
+
+// splits the command line into flags and positional args
+fn parse_args(args: &[String]) -> std::result::Result<(Options, Vec<String>), String> {
+    let mut options = Options { dfa_path: None };
+    let positional = cli::split_args(args, |arg| {
+        if let Some(path) = arg.strip_prefix("--dfa=") {
+            options.dfa_path = Some(path.to_string());
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+    Ok((options, positional))
+}
+
+// the alphabet is the first line of the file, alphabet encoded with whitespace ignored, like line 1 of a scan.u
+fn read_alphabet(path: &str) -> std::result::Result<Vec<u8>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("could not open file '{}': {}", path, e))?;
+    let encoded: String = contents.lines().next().unwrap_or("").chars().filter(|c| !c.is_whitespace()).collect();
+    alphabetencoding::decode(&encoded).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let (options, positional) = parse_args(&args[1..]).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}\n{}", e, USAGE);
+        process::exit(1);
+    });
+    if positional.len() != 3 {
+        eprintln!("ERROR: Argument number\n{}", USAGE);
+        process::exit(1);
+    }
+    let (alphabet_path, pattern, output_path) = (&positional[0], &positional[1], &positional[2]);
+
+    let alphabet = read_alphabet(alphabet_path).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}", e);
+        process::exit(1);
+    });
+    let nfa = regex_to_nfa(pattern, &alphabet).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}", e);
+        process::exit(1);
+    });
+
+    if let Some(path) = &options.dfa_path {
+        let (mut dfa, _) = nfa.subset_construction();
+        dfa.minimize();
+        if let Err(e) = fs::write(path, dfa.print()) {
+            eprintln!("ERROR: Could not write {}: {}", path, e);
+            process::exit(1);
+        }
+    }
+
+    // the file needs some character to call lambda. any byte the alphabet doesn't use will do
+    let Some(lambda) = (0..=255u8).find(|b| !alphabet.contains(b)) else {
+        eprintln!("ERROR: the alphabet uses all 256 bytes, so there is no character left for lambda");
+        process::exit(1);
+    };
+    if let Err(e) = fs::write(output_path, NfaFile { nfa, lambda }.print()) {
+        eprintln!("ERROR: Could not write {}: {}", output_path, e);
+        process::exit(1);
+    }
+}
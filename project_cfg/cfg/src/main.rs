@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::env;
 use std::fs;
+use automata::error::{Error, Result};
 
 #[derive(Debug, Clone)]
 struct Production {
@@ -9,6 +10,7 @@ struct Production {
 }
 
 #[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)] // keep the same naming as DFA and NFA
 struct CFG {
     productions: Vec<Production>,
     non_terminals: HashSet<String>,
@@ -20,7 +22,8 @@ fn is_nonterminal(s: &str) -> bool {
     s.chars().any(|c| c.is_ascii_uppercase())
 }
 
-fn parse_grammar(input: &str) -> CFG {
+// file is only used to give errors some context
+fn parse_grammar(file: &str, input: &str) -> Result<CFG> {
     let mut productions = Vec::new();
     let mut non_terminals = HashSet::new();
     let mut symbols = HashSet::new();
@@ -28,21 +31,23 @@ fn parse_grammar(input: &str) -> CFG {
 
     let mut current_lhs: Option<String> = None;
 
-    for line in input.lines() {
+    let malformed = |line: usize, message: String| Error::MalformedGrammar { file: file.to_string(), line, message };
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line_number = line_index + 1;
         let tokens: Vec<&str> = line.split_whitespace().collect();
 
-        if tokens.len() == 0 {
+        if tokens.is_empty() {
             continue;
         }
 
-        let mut i = 0;
+        let mut i;
 
         // New LHS rule
         if tokens.len() >= 2 && tokens[1] == "->" {
             let lhs = tokens[0].to_string();
             if !is_nonterminal(&lhs) {
-                eprintln!("{} should be a nonterminal but isnt!", lhs);
-                std::process::exit(1);
+                return Err(malformed(line_number, format!("{} should be a nonterminal but isnt!", lhs)));
             }
             non_terminals.insert(lhs.clone());
             current_lhs = Some(lhs.clone());
@@ -57,10 +62,15 @@ fn parse_grammar(input: &str) -> CFG {
             i = 1;
         }
         else {
-            eprintln!("Malformed grammar");
-            std::process::exit(1);
+            return Err(malformed(line_number, format!("expected `A -> ...` or `| ...`, read {}", line.trim())));
         }
 
+        // a | line needs an earlier rule to continue
+        let lhs = match &current_lhs {
+            Some(lhs) => lhs.clone(),
+            None => return Err(malformed(line_number, "alternative has no rule to belong to".to_string())),
+        };
+
         let mut rhs = Vec::new();
 
         // everything else should be a rhs production
@@ -69,7 +79,7 @@ fn parse_grammar(input: &str) -> CFG {
 
             if token == "|" {
                 // finish current production
-                productions.push(Production {lhs: current_lhs.clone().expect("asdlfknaksdvjn"), rhs: rhs.clone()});
+                productions.push(Production {lhs: lhs.clone(), rhs: rhs.clone()});
                 rhs.clear();
             } else {
                 if token != "lambda" {
@@ -86,11 +96,90 @@ fn parse_grammar(input: &str) -> CFG {
         }
 
         if !rhs.is_empty() {
-            productions.push(Production {lhs: current_lhs.clone().expect("lafknsdlkavlsvn"), rhs});
+            productions.push(Production {lhs, rhs});
+        }
+    }
+
+    Ok(CFG {productions, non_terminals, symbols, start_symbol})
+}
+
+// nonterminals that can derive the empty string
+fn derives_to_lambda(grammar: &CFG) -> HashSet<String> {
+    let mut nullable: HashSet<String> = HashSet::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for p in &grammar.productions {
+            if !nullable.contains(&p.lhs) && p.rhs.iter().all(|s| s == "lambda" || nullable.contains(s)) {
+                nullable.insert(p.lhs.clone());
+                changed = true;
+            }
+        }
+    }
+    nullable
+}
+
+// the terminals a string of symbols can start with, and whether it can derive the empty string
+fn first_of(rhs: &[String], first: &HashMap<String, BTreeSet<String>>, nullable: &HashSet<String>) -> (BTreeSet<String>, bool) {
+    let mut set = BTreeSet::new();
+    for symbol in rhs.iter().filter(|s| *s != "lambda") {
+        if !is_nonterminal(symbol) {
+            set.insert(symbol.clone());
+            return (set, false);
+        }
+        set.extend(first.get(symbol).into_iter().flatten().cloned());
+        if !nullable.contains(symbol) {
+            return (set, false);
+        }
+    }
+    (set, true)
+}
+
+// the terminal each production can start with, or that can follow its lhs when it derives nothing.
+// the grammar is LL(1) when the productions of every nonterminal have disjoint predict sets
+fn predict_sets(grammar: &CFG) -> Vec<BTreeSet<String>> {
+    let nullable = derives_to_lambda(grammar);
+
+    let mut first: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for p in &grammar.productions {
+            let (set, _) = first_of(&p.rhs, &first, &nullable);
+            let entry = first.entry(p.lhs.clone()).or_default();
+            let before = entry.len();
+            entry.extend(set);
+            changed |= entry.len() != before;
         }
     }
 
-    CFG {productions, non_terminals, symbols, start_symbol}
+    let mut follow: HashMap<String, BTreeSet<String>> = HashMap::new();
+    changed = true;
+    while changed {
+        changed = false;
+        for p in &grammar.productions {
+            for (i, symbol) in p.rhs.iter().enumerate().filter(|(_, s)| is_nonterminal(s)) {
+                let (mut set, rest_nullable) = first_of(&p.rhs[i + 1..], &first, &nullable);
+                if rest_nullable {
+                    set.extend(follow.get(&p.lhs).into_iter().flatten().cloned());
+                }
+                let entry = follow.entry(symbol.clone()).or_default();
+                let before = entry.len();
+                entry.extend(set);
+                changed |= entry.len() != before;
+            }
+        }
+    }
+
+    grammar.productions.iter()
+        .map(|p| {
+            let (mut set, rhs_nullable) = first_of(&p.rhs, &first, &nullable);
+            if rhs_nullable {
+                set.extend(follow.get(&p.lhs).into_iter().flatten().cloned());
+            }
+            set
+        })
+        .collect()
 }
 
 fn main() {
@@ -106,7 +195,10 @@ fn main() {
         std::process::exit(1);
     });
 
-    let grammar = parse_grammar(&input);
+    let grammar = parse_grammar(path, &input).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
 
     println!("Grammar Non-Terminals");
     for nt in &grammar.non_terminals {
@@ -131,4 +223,31 @@ fn main() {
     }
 
     println!("\nGrammar Start Symbol or Goal: {}", grammar.start_symbol);
+
+    let predict = predict_sets(&grammar);
+    println!("\nPredict Sets");
+    for (i, set) in predict.iter().enumerate() {
+        println!("({}) {}", i + 1, set.iter().cloned().collect::<Vec<_>>().join(", "));
+    }
+
+    // every pair of productions for the same nonterminal that a lookahead can't choose between
+    let mut conflicts = Vec::new();
+    for (i, a) in grammar.productions.iter().enumerate() {
+        for (j, b) in grammar.productions.iter().enumerate().skip(i + 1) {
+            let shared: Vec<String> = predict[i].intersection(&predict[j]).cloned().collect();
+            if a.lhs == b.lhs && !shared.is_empty() {
+                conflicts.push(format!("({}) and ({}) for {} both predict {}", i + 1, j + 1, a.lhs, shared.join(", ")));
+            }
+        }
+    }
+    if conflicts.is_empty() {
+        println!("\nThe grammar is LL(1)");
+    } else {
+        println!("\nThe grammar is not LL(1):");
+        for conflict in conflicts {
+            println!("{}", conflict);
+        }
+        // so scripts and tests can check a grammar stays LL(1)
+        std::process::exit(1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,29 @@
+use std::env;
+use std::fs;
+use std::process::{Command, Output};
+
+fn run_cfg(path: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cfg")).arg(path).output().unwrap()
+}
+
+// the regex parser in automata is written against this grammar and needs it to stay LL(1)
+#[test]
+fn regex_grammar_is_ll1() {
+    let output = run_cfg(concat!(env!("CARGO_MANIFEST_DIR"), "/../../automata/src/regex.cfg"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("The grammar is LL(1)"), "{}", stdout);
+}
+
+#[test]
+fn conflicts_fail() {
+    // both productions of A start with a
+    let path = env::temp_dir().join(format!("cfg_conflict_{}.cfg", std::process::id()));
+    fs::write(&path, "S -> A $\nA -> a b\n    | a c\n").unwrap();
+    let output = run_cfg(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{}", stdout);
+    assert!(stdout.contains("(2) and (3) for A both predict a"), "{}", stdout);
+}
@@ -1,92 +1,138 @@
-mod dfa;
-
+use std::collections::HashMap;
 use std::env;
-use std::fs::File;
-use std::io::{self, BufRead, Write};
-use std::path::Path;
+use std::fs;
+use std::io::Write;
 use std::process;
-use crate::dfa::{DFA, StateRow};
+use automata::alphabetencoding;
+use automata::cli;
+use automata::codegen::RustStyle;
+use automata::dfa::{DFA, MinimizeAlgorithm, StateIds};
+use automata::ttfile;
 use std::fs::OpenOptions;
 
-fn read_lines<P>(input_path: P) -> io::Result<io::Lines<io::BufReader<File>>>
-    where P: AsRef<Path>, {
-        let file = File::open(input_path)?;
-        Ok(io::BufReader::new(file).lines())
-        // where to throw errors
+const USAGE: &str = "Usage: cargo run -- [--algorithm=hopcroft|stack] [--verify[=<tt_file>]] [--dot=<prefix>] [--rust=<prefix>] [--start=<state_id>] [--original-ids] [--enumerate=<count>] [--map=<file>] [--explain] <input_file> <output_file>\nboth algorithms print the same minimal table: states renumbered breadth first from the start state, with unreachable states and states that can never accept dropped (edges into them become E)";
+
+// the first count accepted strings in shortlex order, one per line. the empty string shows up as ""
+fn first_accepted(dfa: &DFA, count: usize) -> Vec<String> {
+    // a shortest accepted string is under size() symbols long and each next one at most size() more,
+    // so this is always long enough to find count of them if the language has that many
+    let max_len = dfa.size() * (count + 2);
+    dfa.enumerate(max_len)
+        .take(count)
+        .map(|token| if token.is_empty() { "\"\"".to_string() } else { token })
+        .collect()
 }
 
-// 
-fn get_tt_from_file(input_path: &str) -> Vec<StateRow> {
-    // get lines for the tt
-    let mut is_first_line = true;
-    let mut num_transitions = u32::MAX;
-
-    let mut tt: Vec<StateRow> = Vec::new();
-
-    if let Ok(lines) = read_lines(input_path) {
-        for line in lines.map_while(Result::ok) {
-            let delimited_line: Vec<&str> = line.split_whitespace().collect();
+// one `old new` line per input state, both named like the tables they come from. E if the state was dropped
+fn merge_map(original: &DFA, minimized: &DFA, merged: &[Option<u32>], ids: StateIds) -> String {
+    let name = |state: u32| match ids {
+        StateIds::Dense => state,
+        StateIds::Original => minimized.original_ids[state as usize],
+    };
+    let mut output = String::new();
+    for (old, new) in merged.iter().enumerate() {
+        let new = new.map_or("E".to_string(), |new| name(new).to_string());
+        output.push_str(&format!("{} {}\n", original.original_ids[old], new));
+    }
+    output
+}
 
-            let mut current_row = StateRow {
-                accepting : false,
-                state_id : u32::MAX,
-                transitions : Vec::new(),
+// a shortest distinguishing suffix for every pair of states, one `a b: suffix` line each
+// the suffix is columns, alphabet encoded, like the counterexamples from --verify
+fn explain(dfa: &DFA, ids: StateIds) -> Vec<String> {
+    let name = |state: usize| match ids {
+        StateIds::Dense => state as u32,
+        StateIds::Original => dfa.original_ids[state],
+    };
+    let mut lines = Vec::new();
+    for a in 0..dfa.size() {
+        for b in a + 1..dfa.size() {
+            let suffix = match dfa.distinguishing_suffix(a, b) {
+                Some(suffix) if suffix.is_empty() => "\"\"".to_string(),
+                Some(suffix) => alphabetencoding::encode(&suffix),
+                // only the stack algorithm can leave equivalent states behind
+                None => "none, these states are equivalent".to_string(),
             };
+            lines.push(format!("{} {}: {}", name(a), name(b), suffix));
+        }
+    }
+    lines
+}
 
-            // on first line, check number of transitions we should have
-            if is_first_line {
-                num_transitions = delimited_line.len() as u32 - 2;
-                is_first_line = false;
-            }
-            else if num_transitions != (delimited_line.len() as u32 - 2) {
-                eprintln!("ERROR (malformed tt): Based on first line of tt, expected {} transitions, but got {} transitions.", num_transitions, delimited_line.len() - 2);
-                process::exit(1);
-            }
-
-            // first symbol needs to be a +/-
-            if delimited_line[0] != "+" && delimited_line[0] != "-" {
-                eprintln!("ERROR: first symbol of line must be +/-, read {}", delimited_line[0]);
-                process::exit(1);
-            }
-            else if delimited_line[0] == "+" {
-                current_row.accepting = true;
-            }
-
-            // second symbol needs to be an int
-            current_row.state_id = delimited_line[1].parse::<u32>().unwrap_or_else(|_| {
-                eprintln!("ERROR: read second symbol of transition table as {} and expected a nonnegative integer!", delimited_line[1]);
-                process::exit(1);
-            });
+// flags go anywhere on the command line, see automata::cli
+struct Options {
+    algorithm: MinimizeAlgorithm,
+    // check that minimizing kept the language
+    verify: bool,
+    // also check the input against this table, e.g. a hand written one
+    verify_against: Option<String>,
+    // write <prefix>.before.dot and <prefix>.after.dot, coloured by which states were merged
+    dot_prefix: Option<String>,
+    // write <prefix>.match.rs and <prefix>.table.rs, the minimized DFA as standalone rust
+    rust_prefix: Option<String>,
+    // start state by its id in the input table, instead of state 0
+    start: Option<u32>,
+    // write each state under an id from the input table instead of renumbering 0..n
+    original_ids: bool,
+    // print this many accepted strings of the input and of the minimized DFA
+    enumerate: Option<usize>,
+    // write which output state each input state became
+    map_path: Option<String>,
+    // print a shortest suffix telling apart each pair of output states
+    explain: bool,
+}
 
-            // everything else is a transition holding some int or E
-            for i in 2..=(delimited_line.len() as u32 - 1)  {
-                if delimited_line[i as usize] == "E" {
-                    current_row.transitions.push(None);
-                }
-                else {
-                    let transition = delimited_line[i as usize].parse::<u32>().unwrap_or_else(|_| {
-                        eprintln!("ERROR (invalid transition): read {} and expected E or a nonnegative integer!", delimited_line[i as usize]);
-                        process::exit(1);
-                    });
-                    current_row.transitions.push(Some(transition));
-                }
-            }
-            tt.push(current_row.clone());
+// splits the command line into flags and positional args
+fn parse_args(args: &[String]) -> std::result::Result<(Options, Vec<String>), String> {
+    let mut options = Options { algorithm: MinimizeAlgorithm::Hopcroft, verify: false, verify_against: None, dot_prefix: None, rust_prefix: None, start: None, original_ids: false, enumerate: None, map_path: None, explain: false };
+    let positional = cli::split_args(args, |arg| {
+        if let Some(algorithm) = arg.strip_prefix("--algorithm=") {
+            options.algorithm = match algorithm {
+                "hopcroft" => MinimizeAlgorithm::Hopcroft,
+                "stack" => MinimizeAlgorithm::StackRefinement,
+                _ => return Err(format!("unknown algorithm {}", algorithm)),
+            };
+        } else if arg == "--verify" {
+            options.verify = true;
+        } else if let Some(path) = arg.strip_prefix("--verify=") {
+            options.verify = true;
+            options.verify_against = Some(path.to_string());
+        } else if let Some(prefix) = arg.strip_prefix("--dot=") {
+            options.dot_prefix = Some(prefix.to_string());
+        } else if let Some(prefix) = arg.strip_prefix("--rust=") {
+            options.rust_prefix = Some(prefix.to_string());
+        } else if let Some(start) = arg.strip_prefix("--start=") {
+            options.start = Some(start.parse().map_err(|_| format!("start state must be a state id, got {}", start))?);
+        } else if arg == "--original-ids" {
+            options.original_ids = true;
+        } else if let Some(count) = arg.strip_prefix("--enumerate=") {
+            options.enumerate = Some(count.parse().map_err(|_| format!("enumerate needs a count, got {}", count))?);
+        } else if let Some(path) = arg.strip_prefix("--map=") {
+            options.map_path = Some(path.to_string());
+        } else if arg == "--explain" {
+            options.explain = true;
+        } else {
+            return Ok(false);
         }
-    }
-    tt
+        Ok(true)
+    })?;
+    Ok((options, positional))
 }
 
 fn main() {
     // need to read from files
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 3 {
-        eprintln!("ERROR: Argument number\nUsage: cargo run -- <input_file> <output_file>");
+    let (options, positional) = parse_args(&args[1..]).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}\n{}", e, USAGE);
+        process::exit(1);
+    });
+    if positional.len() != 2 {
+        eprintln!("ERROR: Argument number\n{}", USAGE);
         process::exit(1);
     }
-    let input_path = &args[1];
-    let output_path = &args[2];
+    let input_path = &positional[0];
+    let output_path = &positional[1];
 
     // jank order but I want to check ifwe can access the output file before doing all this extra work
     let mut output_file = match OpenOptions::new()
@@ -103,25 +149,100 @@ fn main() {
     };
 
     // parse input into a transition table
-    let tt = get_tt_from_file(input_path);
-
-    // // Debug
-    // for row in &tt {
-    //     println!("{:?}", row); // need debug flag for this
-    // }
-    
-    // Create DFA object
-    let mut dfa = DFA::new(tt).expect("Failed to create DFA");
+    // minimizing only looks at the table, so the alphabet map can stay empty
+    let mut dfa = ttfile::load_dfa(input_path, HashMap::new(), options.start).unwrap_or_else(|e| {
+        eprintln!("ERROR: {}", e);
+        process::exit(1);
+    });
 
     // println!("Initial DFA:\n{}", dfa.print()); // DEBUG
     
+    // hang on to the input so we can check the result against it
+    let original = dfa.clone();
+
     // call minimize
-    dfa.minimize();
+    let merged = dfa.minimize_with(options.algorithm);
 
-    println!("Final DFA:\n{}", dfa.print()); // DEBUG
+    let ids = if options.original_ids { StateIds::Original } else { StateIds::Dense };
+    let minimized = dfa.print_with(ids);
+    println!("Final DFA:\n{}", minimized); // DEBUG
+
+    if let Some(path) = &options.map_path {
+        if let Err(e) = fs::write(path, merge_map(&original, &dfa, &merged, ids)) {
+            eprintln!("ERROR: Could not write {}: {}", path, e);
+            process::exit(1);
+        }
+    }
+
+    if options.explain {
+        println!("Distinguishing suffixes:\n{}\n", explain(&dfa, ids).join("\n"));
+    }
+
+    if let Some(count) = options.enumerate {
+        let before = first_accepted(&original, count);
+        let after = first_accepted(&dfa, count);
+        println!("First {} accepted strings before minimizing:\n{}\n", count, before.join("\n"));
+        println!("First {} accepted strings after minimizing:\n{}\n", count, after.join("\n"));
+        if before != after {
+            eprintln!("WARNING: minimizing changed the first {} accepted strings", count);
+        }
+    }
+
+    if let Some(prefix) = &options.dot_prefix {
+        // every original state takes the colour of the state it was merged into, the same map --map writes
+        let merged_into: Vec<Option<usize>> = merged.iter().map(|s| s.map(|s| s as usize)).collect();
+        let kept: Vec<Option<usize>> = (0..dfa.size()).map(Some).collect();
+
+        for (suffix, graph) in [("before", original.to_dot_with_groups(&merged_into)), ("after", dfa.to_dot_with_groups(&kept))] {
+            let dot_path = format!("{}.{}.dot", prefix, suffix);
+            if let Err(e) = fs::write(&dot_path, graph) {
+                eprintln!("ERROR: Could not write {}: {}", dot_path, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(prefix) = &options.rust_prefix {
+        for (suffix, style) in [("match", RustStyle::Match), ("table", RustStyle::Table)] {
+            let rust_path = format!("{}.{}.rs", prefix, suffix);
+            if let Err(e) = fs::write(&rust_path, dfa.to_rust(style)) {
+                eprintln!("ERROR: Could not write {}: {}", rust_path, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if options.verify {
+        let mut checks = vec![(input_path.clone(), original.clone(), "the minimized DFA".to_string(), dfa.clone())];
+        if let Some(other_path) = &options.verify_against {
+            let other = ttfile::load_dfa(other_path, HashMap::new(), None).unwrap_or_else(|e| {
+                eprintln!("ERROR: {}", e);
+                process::exit(1);
+            });
+            checks.push((input_path.clone(), original.clone(), other_path.clone(), other));
+        }
+
+        let mut all_equivalent = true;
+        for (left_name, left, right_name, right) in &checks {
+            match left.equivalent(right) {
+                Ok(()) => println!("VERIFIED: {} and {} accept the same language", left_name, right_name),
+                Err(counterexample) => {
+                    all_equivalent = false;
+                    let accepted_by = if left.accepts_counterexample(&counterexample) { left_name } else { right_name };
+                    eprintln!(
+                        "ERROR: {} and {} differ. Shortest counterexample (columns, alphabet encoded): '{}' is only accepted by {}",
+                        left_name, right_name, alphabetencoding::encode(&counterexample), accepted_by
+                    );
+                }
+            }
+        }
+        if !all_equivalent {
+            process::exit(1);
+        }
+    }
 
-    if let Err(_) = output_file.write_all(dfa.print().as_bytes()) {
-        eprintln!("ERROR: Could not write dfa to output file {}\n. Will print dfa to stderr: {}", output_path, dfa.print());
+    if output_file.write_all(minimized.as_bytes()).is_err() {
+        eprintln!("ERROR: Could not write dfa to output file {}\n. Will print dfa to stderr: {}", output_path, minimized);
         process::exit(1);
     }
     